@@ -0,0 +1,123 @@
+//! Attributes that register a day's solvers and parsers with
+//! `common::registry`, so the runner finds them without a hand-kept table
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{meta, parse_macro_input, Error, ItemFn, LitInt, LitStr};
+
+#[derive(Default)]
+struct Args {
+    day: Option<LitInt>,
+    part: Option<LitInt>,
+    name: Option<LitStr>,
+}
+
+fn required(value: Option<LitInt>, key: &str) -> syn::Result<LitInt> {
+    value.ok_or_else(|| Error::new(Span::call_site(), format!("expected `{key} = N`")))
+}
+
+/// Registers a solver for one part of a day. The function takes the puzzle
+/// input and returns its answer as a `String` or `common::Result<String>`.
+///
+/// ```ignore
+/// #[aoc(day = 6, part = 2)]
+/// pub fn process_part2(input: &str) -> String { ... }
+///
+/// // Other ways of solving a part need a name, for `--impl` and `aoc compare`
+/// #[aoc(day = 6, part = 2, name = "loop")]
+/// pub fn process_part2_loop(input: &str) -> String { ... }
+/// ```
+#[proc_macro_attribute]
+pub fn aoc(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut args = Args::default();
+    let parser = meta::parser(|meta| {
+        if meta.path.is_ident("day") {
+            args.day = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("part") {
+            args.part = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("name") {
+            args.name = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("expected `day`, `part` or `name`"));
+        }
+        Ok(())
+    });
+    parse_macro_input!(attr with parser);
+    let function = parse_macro_input!(item as ItemFn);
+
+    solver(args, function)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn solver(args: Args, function: ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    let day = required(args.day, "day")?;
+    let part = required(args.part, "part")?;
+    if !matches!(part.base10_parse::<u8>()?, 1 | 2) {
+        return Err(Error::new(part.span(), "part should be 1 or 2"));
+    }
+
+    let ident = &function.sig.ident;
+    let name = match args.name {
+        Some(name) => quote!(#name),
+        None => quote!(::common::registry::DEFAULT),
+    };
+
+    Ok(quote! {
+        #function
+
+        ::common::registry::inventory::submit! {
+            ::common::registry::Solution {
+                day: #day,
+                part: #part,
+                name: #name,
+                solver: |input| ::common::registry::IntoAnswer::into_answer(#ident(input)),
+            }
+        }
+    })
+}
+
+/// Registers a nom parser that reads a day's whole input, so the runner can
+/// check a file against the day's format without solving it
+///
+/// ```ignore
+/// #[aoc_parser(day = 6)]
+/// pub fn race_descriptions(input: &str) -> IResult<&str, Vec<(u32, u32)>> { ... }
+/// ```
+#[proc_macro_attribute]
+pub fn aoc_parser(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut day: Option<LitInt> = None;
+    let parser = meta::parser(|meta| {
+        if meta.path.is_ident("day") {
+            day = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `day`"))
+        }
+    });
+    parse_macro_input!(attr with parser);
+    let function = parse_macro_input!(item as ItemFn);
+
+    input_parser(day, function)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn input_parser(day: Option<LitInt>, function: ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    let day = required(day, "day")?;
+    let ident = &function.sig.ident;
+    let name = ident.to_string();
+
+    Ok(quote! {
+        #function
+
+        ::common::registry::inventory::submit! {
+            ::common::registry::Parser {
+                day: #day,
+                name: #name,
+                parse: |input| ::common::parse::finish(#ident, input).map(|_| ()),
+            }
+        }
+    })
+}
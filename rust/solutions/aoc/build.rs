@@ -0,0 +1,27 @@
+//! Lists the day crates this crate depends on in `$OUT_DIR/linked.rs`, one
+//! `extern crate` each. Nothing else refers to most of them, and a crate that
+//! isn't referred to isn't linked, taking its `#[aoc]` registrations with it.
+
+use std::{env, fs, path::Path};
+
+fn main() {
+    let dir = env::var("CARGO_MANIFEST_DIR").expect("cargo sets CARGO_MANIFEST_DIR");
+    let manifest = Path::new(&dir).join("Cargo.toml");
+    println!("cargo::rerun-if-changed={}", manifest.display());
+
+    let manifest: toml::Table = fs::read_to_string(&manifest)
+        .expect("Cargo.toml should be readable")
+        .parse()
+        .expect("Cargo.toml should be valid TOML");
+
+    let linked: String = manifest["dependencies"]
+        .as_table()
+        .expect("dependencies should be a table")
+        .keys()
+        .filter(|name| name.starts_with("day-"))
+        .map(|name| format!("extern crate {};\n", name.replace('-', "_")))
+        .collect();
+
+    let out = env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    fs::write(Path::new(&out).join("linked.rs"), linked).expect("OUT_DIR should be writable");
+}
@@ -0,0 +1,305 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    fmt,
+};
+
+use serde::{Deserialize, Serialize};
+
+// Counts for one thread, only updated while it's inside `measure`, so that
+// other threads (parallel runs, solvers abandoned after timing out) don't add
+// to a solver's numbers. Logging happens on the solver's own thread, so it's
+// kept out with `uncounted`. Nothing here allocates, so the allocator can use
+// it.
+struct Counters {
+    enabled: Cell<bool>,
+    allocations: Cell<usize>,
+    bytes: Cell<usize>,
+    // Signed, as memory allocated before a measurement may be freed during it
+    current: Cell<isize>,
+    peak: Cell<isize>,
+}
+
+thread_local! {
+    static COUNTERS: Counters = const {
+        Counters {
+            enabled: Cell::new(false),
+            allocations: Cell::new(0),
+            bytes: Cell::new(0),
+            current: Cell::new(0),
+            peak: Cell::new(0),
+        }
+    };
+}
+
+/// Wraps the system allocator and counts allocations made inside [`measure`],
+/// on the thread that called it. Install it in a binary with
+/// `#[global_allocator]`.
+pub struct CountingAllocator;
+
+// A thread that's shutting down has no counters left, and isn't measured
+fn record_alloc(size: usize) {
+    let _ = COUNTERS.try_with(|counters| {
+        if counters.enabled.get() {
+            counters.allocations.set(counters.allocations.get() + 1);
+            counters.bytes.set(counters.bytes.get() + size);
+            counters.current.set(counters.current.get() + size as isize);
+            counters
+                .peak
+                .set(counters.peak.get().max(counters.current.get()));
+        }
+    });
+}
+
+fn record_dealloc(size: usize) {
+    let _ = COUNTERS.try_with(|counters| {
+        if counters.enabled.get() {
+            counters.current.set(counters.current.get() - size as isize);
+        }
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record_dealloc(layout.size());
+    }
+
+    // A realloc is counted as freeing the old block and allocating a new one
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllocStats {
+    pub allocations: usize,
+    pub bytes: usize,
+    pub peak: usize,
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocs, {} allocated, {} peak",
+            self.allocations,
+            human_bytes(self.bytes),
+            human_bytes(self.peak)
+        )
+    }
+}
+
+pub fn human_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    match unit {
+        0 => format!("{bytes} B"),
+        _ => format!("{value:.1} {}", UNITS[unit]),
+    }
+}
+
+/// Runs `f` and reports the heap activity it caused on this thread. Only
+/// meaningful when [`CountingAllocator`] is the global allocator, and not
+/// reentrant.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    let (allocations, bytes, baseline) = COUNTERS.with(|counters| {
+        counters.peak.set(counters.current.get());
+        counters.enabled.set(true);
+        (
+            counters.allocations.get(),
+            counters.bytes.get(),
+            counters.current.get(),
+        )
+    });
+
+    let result = f();
+
+    let stats = COUNTERS.with(|counters| {
+        counters.enabled.set(false);
+        AllocStats {
+            allocations: counters.allocations.get() - allocations,
+            bytes: counters.bytes.get() - bytes,
+            peak: (counters.peak.get() - baseline).max(0) as usize,
+        }
+    });
+
+    (result, stats)
+}
+
+/// Runs `f` without counting its heap activity towards the current
+/// [`measure`], for work done on a solver's thread that isn't the solver's
+pub fn uncounted<T>(f: impl FnOnce() -> T) -> T {
+    let enabled = COUNTERS.with(|counters| counters.enabled.replace(false));
+    let result = f();
+    COUNTERS.with(|counters| counters.enabled.set(enabled));
+    result
+}
+
+/// Wraps a tracing subscriber so that formatting and writing spans and events
+/// isn't counted as the solver's allocations
+#[cfg(feature = "tracing")]
+pub struct Uncounted<S>(pub S);
+
+#[cfg(feature = "tracing")]
+mod uncounted_subscriber {
+    use std::any::TypeId;
+
+    use tracing_core::{
+        span, subscriber::Interest, Dispatch, Event, LevelFilter, Metadata, Subscriber,
+    };
+
+    use super::{uncounted, Uncounted};
+
+    impl<S: Subscriber> Subscriber for Uncounted<S> {
+        fn on_register_dispatch(&self, subscriber: &Dispatch) {
+            uncounted(|| self.0.on_register_dispatch(subscriber))
+        }
+
+        fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+            uncounted(|| self.0.register_callsite(metadata))
+        }
+
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            uncounted(|| self.0.enabled(metadata))
+        }
+
+        fn max_level_hint(&self) -> Option<LevelFilter> {
+            self.0.max_level_hint()
+        }
+
+        fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+            uncounted(|| self.0.new_span(span))
+        }
+
+        fn record(&self, span: &span::Id, values: &span::Record<'_>) {
+            uncounted(|| self.0.record(span, values))
+        }
+
+        fn record_follows_from(&self, span: &span::Id, follows: &span::Id) {
+            uncounted(|| self.0.record_follows_from(span, follows))
+        }
+
+        fn event_enabled(&self, event: &Event<'_>) -> bool {
+            uncounted(|| self.0.event_enabled(event))
+        }
+
+        fn event(&self, event: &Event<'_>) {
+            uncounted(|| self.0.event(event))
+        }
+
+        fn enter(&self, span: &span::Id) {
+            uncounted(|| self.0.enter(span))
+        }
+
+        fn exit(&self, span: &span::Id) {
+            uncounted(|| self.0.exit(span))
+        }
+
+        fn clone_span(&self, id: &span::Id) -> span::Id {
+            uncounted(|| self.0.clone_span(id))
+        }
+
+        fn try_close(&self, id: span::Id) -> bool {
+            uncounted(|| self.0.try_close(id))
+        }
+
+        fn current_span(&self) -> span::Current {
+            uncounted(|| self.0.current_span())
+        }
+
+        // Layers find the registry underneath by downcasting
+        unsafe fn downcast_raw(&self, id: TypeId) -> Option<*const ()> {
+            if id == TypeId::of::<Self>() {
+                Some(self as *const Self as *const ())
+            } else {
+                self.0.downcast_raw(id)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measure_counts_allocations_through_the_allocator() {
+        // The test binary doesn't install the allocator, so drive it directly
+        let layout = Layout::from_size_align(1024, 8).unwrap();
+
+        let (_, stats) = measure(|| unsafe {
+            let a = CountingAllocator.alloc(layout);
+            let b = CountingAllocator.alloc(layout);
+            CountingAllocator.dealloc(a, layout);
+            let b = CountingAllocator.realloc(b, layout, 4096);
+            CountingAllocator.dealloc(b, Layout::from_size_align(4096, 8).unwrap());
+
+            // Other threads' allocations aren't this measurement's
+            std::thread::spawn(move || {
+                let c = CountingAllocator.alloc(layout);
+                CountingAllocator.dealloc(c, layout);
+            })
+            .join()
+            .unwrap();
+        });
+
+        assert_eq!(
+            stats,
+            AllocStats {
+                allocations: 3,
+                bytes: 6144,
+                peak: 4096,
+            }
+        );
+    }
+
+    #[test]
+    fn uncounted_work_is_left_out() {
+        let layout = Layout::from_size_align(1024, 8).unwrap();
+
+        let (_, stats) = measure(|| unsafe {
+            let a = CountingAllocator.alloc(layout);
+            uncounted(|| CountingAllocator.dealloc(CountingAllocator.alloc(layout), layout));
+            CountingAllocator.dealloc(a, layout);
+        });
+
+        assert_eq!(stats.allocations, 1);
+        assert_eq!(stats.bytes, 1024);
+    }
+
+    #[test]
+    fn human_bytes_picks_a_unit() {
+        assert_eq!(human_bytes(512), "512 B");
+        assert_eq!(human_bytes(1536), "1.5 KiB");
+        assert_eq!(human_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
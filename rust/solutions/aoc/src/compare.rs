@@ -0,0 +1,212 @@
+use std::{fmt, path::Path, time::Duration};
+
+use crate::{fixtures, outcome::INPUT, read_input, solve, Day, Error, RunOptions};
+
+/// How one implementation did on an input
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub name: &'static str,
+    /// The answer, or the error it stopped with
+    pub answer: Result<String, String>,
+    /// Fastest of the runs
+    pub elapsed: Duration,
+}
+
+/// Every implementation of a part, run on the same input
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub day: u8,
+    pub part: u8,
+    pub source: String,
+    pub runs: Vec<Run>,
+}
+
+impl Comparison {
+    /// Whether every implementation finished with the same answer
+    pub fn agree(&self) -> bool {
+        let mut answers = self.runs.iter().map(|run| run.answer.as_ref().ok());
+        let first = answers.next().flatten();
+        first.is_some() && answers.all(|answer| answer == first)
+    }
+
+    fn fastest(&self) -> Option<Duration> {
+        self.runs
+            .iter()
+            .filter(|run| run.answer.is_ok())
+            .map(|run| run.elapsed)
+            .min()
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {:02} part {}", self.day, self.part)?;
+        if self.source != INPUT {
+            write!(f, " [{}]", self.source)?;
+        }
+        match (self.runs.len(), self.agree()) {
+            (1, _) => writeln!(f, ": only one implementation")?,
+            (n, true) => writeln!(f, ": {n} implementations agree")?,
+            (n, false) => writeln!(f, ": {n} implementations DISAGREE")?,
+        }
+
+        let width = self
+            .runs
+            .iter()
+            .map(|run| run.name.len())
+            .max()
+            .unwrap_or(0);
+        let fastest = self.fastest();
+        for run in &self.runs {
+            match (&run.answer, fastest) {
+                (Ok(answer), Some(fastest)) => writeln!(
+                    f,
+                    "  {:width$}  {answer}  {:.2?}  {:.2}x",
+                    run.name,
+                    run.elapsed,
+                    run.elapsed.as_secs_f64() / fastest.as_secs_f64().max(f64::EPSILON)
+                )?,
+                (Ok(answer), None) => writeln!(f, "  {:width$}  {answer}", run.name)?,
+                (Err(error), _) => writeln!(f, "  {:width$}  {error}", run.name)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn compare_part(
+    day: &Day,
+    part: u8,
+    source: &str,
+    input: &str,
+    runs: usize,
+    options: &RunOptions,
+) -> Result<Comparison, Error> {
+    let mut results = Vec::new();
+    for (name, _) in day.implementations(part)? {
+        let options = RunOptions {
+            implementation: Some(name.to_string()),
+            ..options.clone()
+        };
+
+        let mut run = Run {
+            name,
+            answer: Err("not run".to_string()),
+            elapsed: Duration::MAX,
+        };
+        for _ in 0..runs.max(1) {
+            match solve(day, part, input, &options) {
+                Ok(answer) => {
+                    run.elapsed = run.elapsed.min(answer.elapsed);
+                    run.answer = Ok(answer.answer);
+                }
+                Err(err) if err.is_solver_failure() => {
+                    // No point repeating a run that didn't finish
+                    run.answer = Err(err.to_string());
+                    break;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        results.push(run);
+    }
+
+    Ok(Comparison {
+        day: day.day,
+        part,
+        source: source.to_string(),
+        runs: results,
+    })
+}
+
+/// Runs every implementation of the given parts on the puzzle input, or on
+/// the day's examples when there isn't one, keeping the fastest of `runs` times
+pub fn compare(
+    root: &Path,
+    day: &Day,
+    parts: &[u8],
+    input: Option<&Path>,
+    runs: usize,
+    options: &RunOptions,
+) -> Result<Vec<Comparison>, Error> {
+    let inputs = match input {
+        Some(path) => vec![(INPUT.to_string(), read_input(path)?, None)],
+        None => fixtures::load_day(root, day.day)?
+            .into_iter()
+            .map(|fixture| (fixture.name, fixture.input, Some(fixture.expected)))
+            .collect(),
+    };
+
+    let mut comparisons = Vec::new();
+    for &part in parts {
+        for (source, input, expected) in &inputs {
+            // Examples are only meant for the parts they have answers for
+            if expected.as_ref().is_some_and(|e| e.part(part).is_none()) {
+                continue;
+            }
+
+            comparisons.push(compare_part(day, part, source, input, runs, options)?);
+        }
+    }
+
+    Ok(comparisons)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{find_day, workspace_root};
+
+    #[test]
+    fn implementations_agree_on_the_examples() {
+        for day in [5, 6, 10] {
+            let day = find_day(day).unwrap();
+            let comparisons = compare(
+                workspace_root(),
+                day,
+                &[1, 2],
+                None,
+                1,
+                &RunOptions::default(),
+            )
+            .unwrap();
+
+            assert!(comparisons.iter().any(|c| c.runs.len() > 1));
+            for comparison in comparisons {
+                assert!(comparison.agree(), "{comparison}");
+            }
+        }
+    }
+
+    #[test]
+    fn disagreement_is_reported() {
+        let comparison = Comparison {
+            day: 6,
+            part: 2,
+            source: INPUT.to_string(),
+            runs: vec![
+                Run {
+                    name: "default",
+                    answer: Ok("71503".to_string()),
+                    elapsed: Duration::from_millis(1),
+                },
+                Run {
+                    name: "loop",
+                    answer: Ok("71502".to_string()),
+                    elapsed: Duration::from_millis(4),
+                },
+            ],
+        };
+
+        assert!(!comparison.agree());
+        assert_eq!(
+            comparison.to_string(),
+            "day 06 part 2: 2 implementations DISAGREE
+  default  71503  1.00ms  1.00x
+  loop     71502  4.00ms  4.00x
+"
+        );
+    }
+}
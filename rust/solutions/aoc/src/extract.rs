@@ -0,0 +1,183 @@
+use std::{fs, path::Path};
+
+use scraper::{ElementRef, Html, Selector};
+
+use crate::{
+    fixtures::{day_fixtures_dir, read_manifest, set_expected, Expected},
+    Error,
+};
+
+/// An example input from a puzzle page, with the answers the page gives for it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Example {
+    pub input: String,
+    pub expected: Expected,
+}
+
+fn selector(css: &str) -> Selector {
+    Selector::parse(css).expect("selectors should be valid CSS")
+}
+
+fn text(element: ElementRef) -> String {
+    element.text().collect()
+}
+
+/// Pulls the examples out of a saved puzzle page. Each part's description is
+/// an `<article class="day-desc">`, whose first `<pre><code>` block is the
+/// example and whose last emphasised `<code>` is the answer for it. Part two
+/// usually repeats the first example, in which case both answers go with it.
+pub fn extract(html: &str) -> Result<Vec<Example>, Error> {
+    let page = Html::parse_document(html);
+    let articles: Vec<ElementRef> = page.select(&selector("article.day-desc")).collect();
+    if articles.is_empty() {
+        return Err(Error::Extract(
+            "no puzzle description on the page".to_string(),
+        ));
+    }
+
+    let mut examples: Vec<Example> = Vec::new();
+    for (part, article) in (1..=2).zip(articles) {
+        let input = article
+            .select(&selector("pre > code"))
+            .next()
+            .map(text)
+            .ok_or_else(|| Error::Extract(format!("part {part} has no example")))?;
+
+        let answer = article
+            .select(&selector("code > em, em > code"))
+            .last()
+            .map(|answer| text(answer).trim().to_string())
+            .ok_or_else(|| Error::Extract(format!("part {part} has no highlighted answer")))?;
+
+        let index = match examples.iter().position(|example| example.input == input) {
+            Some(index) => index,
+            None => {
+                examples.push(Example {
+                    input,
+                    ..Example::default()
+                });
+                examples.len() - 1
+            }
+        };
+
+        let expected = &mut examples[index].expected;
+        match part {
+            1 => expected.part1 = Some(answer),
+            _ => expected.part2 = Some(answer),
+        }
+    }
+
+    Ok(examples)
+}
+
+/// Fixture names for extracted examples, numbered only when there's more than one
+pub fn fixture_names(count: usize) -> Vec<String> {
+    match count {
+        1 => vec!["example".to_string()],
+        _ => (1..=count).map(|n| format!("example-{n}")).collect(),
+    }
+}
+
+/// Writes examples into a day's fixtures, adding their answers to its
+/// manifest. Existing fixtures are left alone unless `overwrite` is set.
+pub fn write_fixtures(
+    root: &Path,
+    day: u8,
+    examples: &[Example],
+    overwrite: bool,
+) -> Result<Vec<String>, Error> {
+    let dir = day_fixtures_dir(root, day);
+    let manifest = read_manifest(&dir)?;
+    let names = fixture_names(examples.len());
+
+    for (name, example) in names.iter().zip(examples) {
+        let path = dir.join(format!("{name}.txt"));
+        if !overwrite && (path.exists() || manifest.contains_key(name)) {
+            return Err(Error::Extract(format!(
+                "{} already exists, pass --overwrite to replace it",
+                path.display()
+            )));
+        }
+
+        fs::create_dir_all(&dir)
+            .and_then(|_| fs::write(&path, &example.input))
+            .map_err(|err| Error::Fixture {
+                path: path.clone(),
+                message: err.to_string(),
+            })?;
+        set_expected(&dir, name, &example.expected)?;
+    }
+
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::load_day, workspace_root};
+
+    fn page(day: u8) -> String {
+        let path = workspace_root()
+            .join("tests")
+            .join("pages")
+            .join(format!("day-{day:02}.html"));
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn separate_examples_for_each_part() {
+        let examples = extract(&page(1)).unwrap();
+
+        assert_eq!(examples.len(), 2);
+        assert!(examples[0].input.starts_with("1abc2\n"));
+        assert_eq!(examples[0].expected.part(1), Some("142"));
+        assert_eq!(examples[1].expected.part(2), Some("281"));
+    }
+
+    #[test]
+    fn repeated_example_gets_both_answers() {
+        let examples = extract(&page(6)).unwrap();
+
+        assert_eq!(
+            examples,
+            [Example {
+                input: "Time:      7  15   30\nDistance:  9  40  200\n".to_string(),
+                expected: Expected {
+                    part1: Some("288".to_string()),
+                    part2: Some("71503".to_string()),
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn written_fixtures_match_the_hand_made_ones() {
+        let root = tempfile::tempdir().unwrap();
+
+        for day in [1, 6] {
+            write_fixtures(root.path(), day, &extract(&page(day)).unwrap(), false).unwrap();
+
+            let written = load_day(root.path(), day).unwrap();
+            let existing = load_day(workspace_root(), day).unwrap();
+            assert_eq!(written.len(), existing.len());
+            for (written, existing) in written.iter().zip(&existing) {
+                assert_eq!(written.name, existing.name);
+                assert_eq!(written.input, existing.input);
+                assert_eq!(written.expected, existing.expected);
+            }
+        }
+
+        assert!(matches!(
+            write_fixtures(root.path(), 6, &extract(&page(6)).unwrap(), false),
+            Err(Error::Extract(_))
+        ));
+    }
+
+    #[test]
+    fn page_without_a_puzzle_is_an_error() {
+        assert!(matches!(
+            extract("<html><body>404 Not Found</body></html>"),
+            Err(Error::Extract(_))
+        ));
+    }
+}
@@ -0,0 +1,311 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{input_path, Error};
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+pub const YEAR: u16 = 2023;
+
+/// Environment variable holding the value of the site's `session` cookie
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+// Asked for by the site, so its maintainers know who to contact about traffic
+const USER_AGENT: &str = "github.com/tvhees/2023 aoc runner";
+
+/// Minimum time between requests, shared between runs through a stamp file
+pub const MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Blocking GET with the session cookie attached, so tests can swap in a
+/// client that talks to a local stand-in for the site
+pub trait Http {
+    fn get(&self, url: &str, session: &str) -> Result<String, String>;
+}
+
+pub struct UreqClient {
+    agent: ureq::Agent,
+}
+
+impl Default for UreqClient {
+    fn default() -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(Duration::from_secs(30)))
+            .user_agent(USER_AGENT)
+            .build()
+            .into();
+
+        Self { agent }
+    }
+}
+
+impl Http for UreqClient {
+    fn get(&self, url: &str, session: &str) -> Result<String, String> {
+        let mut response = self
+            .agent
+            .get(url)
+            .header("Cookie", &format!("session={session}"))
+            .call()
+            .map_err(|err| match err {
+                // The site answers with a 400 when the session has expired
+                ureq::Error::StatusCode(400) => "session token was rejected".to_string(),
+                ureq::Error::StatusCode(404) => "puzzle isn't available yet".to_string(),
+                err => err.to_string(),
+            })?;
+
+        response
+            .body_mut()
+            .read_to_string()
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// Reads the session token from `AOC_SESSION`, or failing that from
+/// `~/.config/aoc/session`
+pub fn session_token() -> Result<String, Error> {
+    if let Ok(token) = env::var(SESSION_VAR) {
+        return Ok(token.trim().to_string());
+    }
+
+    let path = session_path()
+        .ok_or_else(|| Error::Fetch(format!("set {SESSION_VAR} to the site's session cookie")))?;
+
+    fs::read_to_string(&path)
+        .map(|token| token.trim().to_string())
+        .map_err(|err| {
+            Error::Fetch(format!(
+                "set {SESSION_VAR}, or put the session cookie in {}: {err}",
+                path.display()
+            ))
+        })
+}
+
+fn session_path() -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config.join("aoc").join("session"))
+}
+
+/// Spaces requests out by `interval`, remembering the last request in a file
+/// so separate `aoc fetch` runs don't add up to a burst
+pub struct RateLimiter {
+    pub stamp: PathBuf,
+    pub interval: Duration,
+}
+
+impl RateLimiter {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            stamp: root.join("target").join("aoc-last-fetch"),
+            interval: MIN_INTERVAL,
+        }
+    }
+
+    fn now() -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+
+    /// Sleeps until the next request is allowed, and claims it
+    pub fn wait(&self) -> Result<(), Error> {
+        let last = fs::read_to_string(&self.stamp)
+            .ok()
+            .and_then(|millis| millis.trim().parse().ok())
+            .map(Duration::from_millis);
+
+        if let Some(wait) = last.and_then(|last| (last + self.interval).checked_sub(Self::now())) {
+            thread::sleep(wait);
+        }
+
+        if let Some(dir) = self.stamp.parent() {
+            fs::create_dir_all(dir).map_err(|err| Error::Fetch(err.to_string()))?;
+        }
+        fs::write(&self.stamp, Self::now().as_millis().to_string())
+            .map_err(|err| Error::Fetch(format!("{}: {err}", self.stamp.display())))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fetched {
+    /// The input was already on disk, so nothing was requested
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+pub struct Fetcher<H> {
+    pub http: H,
+    pub base_url: String,
+    pub limiter: RateLimiter,
+}
+
+impl Fetcher<UreqClient> {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            http: UreqClient::default(),
+            base_url: BASE_URL.to_string(),
+            limiter: RateLimiter::new(root),
+        }
+    }
+}
+
+impl<H: Http> Fetcher<H> {
+    /// Downloads a day's input to `day-XX/input.txt`, unless it's already there.
+    /// Inputs never change, so a cached day is never requested again, and
+    /// `session` is only asked for the token when there's a download to make.
+    pub fn fetch(
+        &self,
+        root: &Path,
+        day: u8,
+        session: impl FnOnce() -> Result<String, Error>,
+    ) -> Result<Fetched, Error> {
+        let path = input_path(root, day);
+        if path.exists() {
+            return Ok(Fetched::Cached(path));
+        }
+
+        let session = session()?;
+        self.limiter.wait()?;
+
+        let url = format!("{}/{YEAR}/day/{day}/input", self.base_url);
+        let input = self
+            .http
+            .get(&url, &session)
+            .map_err(|err| Error::Fetch(format!("day {day:02}: {err}")))?;
+
+        // Write then rename, so an interrupted download doesn't look cached
+        let partial = path.with_extension("txt.partial");
+        fs::create_dir_all(path.parent().expect("input should live in a day folder"))
+            .and_then(|_| fs::write(&partial, input))
+            .and_then(|_| fs::rename(&partial, &path))
+            .map_err(|source| Error::Input {
+                path: path.clone(),
+                source,
+            })?;
+
+        Ok(Fetched::Downloaded(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{cell::RefCell, time::Instant};
+
+    use tiny_http::{Header, Response, Server};
+
+    fn limiter(root: &Path, interval: Duration) -> RateLimiter {
+        RateLimiter {
+            stamp: root.join("stamp"),
+            interval,
+        }
+    }
+
+    // Records requests instead of making them
+    #[derive(Default)]
+    struct Recorder(RefCell<Vec<String>>);
+
+    impl Http for &Recorder {
+        fn get(&self, url: &str, _session: &str) -> Result<String, String> {
+            self.0.borrow_mut().push(url.to_string());
+            Ok("0 3 6 9\n".to_string())
+        }
+    }
+
+    fn token(token: &str) -> impl FnOnce() -> Result<String, Error> + '_ {
+        move || Ok(token.to_string())
+    }
+
+    #[test]
+    fn fetches_once_and_spaces_out_requests() {
+        let root = tempfile::tempdir().unwrap();
+        let recorder = Recorder::default();
+        let fetcher = Fetcher {
+            http: &recorder,
+            base_url: "http://stand-in".to_string(),
+            limiter: limiter(root.path(), Duration::from_millis(100)),
+        };
+
+        let start = Instant::now();
+        let path = input_path(root.path(), 9);
+        assert_eq!(
+            fetcher.fetch(root.path(), 9, token("token")).unwrap(),
+            Fetched::Downloaded(path.clone())
+        );
+        assert_eq!(
+            fetcher.fetch(root.path(), 9, token("token")).unwrap(),
+            Fetched::Cached(path.clone())
+        );
+        fetcher.fetch(root.path(), 10, token("token")).unwrap();
+
+        // A cached day doesn't need a token at all
+        let no_token = || Err(Error::Fetch("no session token".to_string()));
+        assert_eq!(
+            fetcher.fetch(root.path(), 9, no_token).unwrap(),
+            Fetched::Cached(path.clone())
+        );
+
+        // The stamp only keeps milliseconds, so allow for rounding
+        assert!(start.elapsed() >= Duration::from_millis(99));
+        assert_eq!(fs::read_to_string(path).unwrap(), "0 3 6 9\n");
+        assert_eq!(
+            *recorder.0.borrow(),
+            [
+                "http://stand-in/2023/day/9/input",
+                "http://stand-in/2023/day/10/input"
+            ]
+        );
+    }
+
+    #[test]
+    fn downloads_from_a_local_stand_in() {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", server.server_addr());
+
+        let site = thread::spawn(move || {
+            for request in server.incoming_requests().take(2) {
+                let cookie = request
+                    .headers()
+                    .iter()
+                    .find(|header| header.field.equiv("Cookie"))
+                    .map(|header| header.value.to_string());
+
+                let response = match (request.url(), cookie.as_deref()) {
+                    (_, Some("session=token")) => Response::from_string("Time: 7\n"),
+                    _ => {
+                        Response::from_string("Puzzle inputs differ by user").with_status_code(400)
+                    }
+                };
+                let response =
+                    response.with_header(Header::from_bytes("Content-Type", "text/plain").unwrap());
+                request.respond(response).unwrap();
+            }
+        });
+
+        let root = tempfile::tempdir().unwrap();
+        let fetcher = Fetcher {
+            http: UreqClient::default(),
+            base_url,
+            limiter: limiter(root.path(), Duration::ZERO),
+        };
+
+        assert!(matches!(
+            fetcher.fetch(root.path(), 6, token("expired")),
+            Err(Error::Fetch(message)) if message.contains("session token was rejected")
+        ));
+        assert!(!input_path(root.path(), 6).exists());
+
+        fetcher.fetch(root.path(), 6, token("token")).unwrap();
+        assert_eq!(
+            fs::read_to_string(input_path(root.path(), 6)).unwrap(),
+            "Time: 7\n"
+        );
+
+        site.join().unwrap();
+    }
+}
@@ -0,0 +1,281 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+pub const MANIFEST: &str = "answers.toml";
+
+/// Expected answers for one fixture, keyed by its file stem in `answers.toml`
+///
+/// ```toml
+/// [example]
+/// part1 = "4361"
+/// part2 = "467835"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Expected {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part2: Option<String>,
+}
+
+impl Expected {
+    pub fn part(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            2 => self.part2.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+pub type Manifest = BTreeMap<String, Expected>;
+
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    pub day: u8,
+    pub name: String,
+    pub path: PathBuf,
+    pub input: String,
+    pub expected: Expected,
+}
+
+pub fn fixtures_dir(root: &Path) -> PathBuf {
+    root.join("tests").join("fixtures")
+}
+
+pub fn day_fixtures_dir(root: &Path, day: u8) -> PathBuf {
+    fixtures_dir(root).join(format!("day-{day:02}"))
+}
+
+fn fixture_error(path: &Path, message: impl Into<String>) -> Error {
+    Error::Fixture {
+        path: path.to_path_buf(),
+        message: message.into(),
+    }
+}
+
+pub fn read_manifest(dir: &Path) -> Result<Manifest, Error> {
+    let path = dir.join(MANIFEST);
+    if !path.exists() {
+        return Ok(Manifest::new());
+    }
+
+    let contents =
+        fs::read_to_string(&path).map_err(|err| fixture_error(&path, err.to_string()))?;
+    toml::from_str(&contents).map_err(|err| fixture_error(&path, err.to_string()))
+}
+
+/// Sets a fixture's expected answers in the day's `answers.toml`, replacing
+/// its table if it has one and adding one at the end if not. The rest of the
+/// file, comments included, is left as it was.
+pub fn set_expected(dir: &Path, name: &str, expected: &Expected) -> Result<(), Error> {
+    let path = dir.join(MANIFEST);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(fixture_error(&path, err.to_string())),
+    };
+
+    let table = toml::to_string(&Manifest::from([(name.to_string(), expected.clone())]))
+        .map_err(|err| fixture_error(&path, err.to_string()))?;
+    let header = table
+        .lines()
+        .next()
+        .expect("a table starts with its header");
+
+    let lines: Vec<&str> = contents.lines().collect();
+    let updated = match lines.iter().position(|line| line.trim() == header) {
+        Some(start) => {
+            // The table runs up to the next one, less any comments and blank
+            // lines leading into it
+            let next = lines[start + 1..]
+                .iter()
+                .position(|line| line.trim_start().starts_with('['))
+                .map_or(lines.len(), |n| start + 1 + n);
+            let end = lines[start + 1..next]
+                .iter()
+                .rposition(|line| {
+                    let line = line.trim();
+                    !line.is_empty() && !line.starts_with('#')
+                })
+                .map_or(start + 1, |n| start + 2 + n);
+
+            let mut updated = lines[..start].to_vec();
+            updated.extend(table.lines());
+            updated.extend(&lines[end..]);
+            updated.join("\n") + "\n"
+        }
+        None => match contents.trim_end() {
+            "" => table,
+            before => format!("{before}\n\n{table}"),
+        },
+    };
+
+    fs::create_dir_all(dir)
+        .and_then(|_| fs::write(&path, updated))
+        .map_err(|err| fixture_error(&path, err.to_string()))
+}
+
+/// Loads every `*.txt` fixture for a day. Each one needs an entry in the
+/// day's `answers.toml`, and every entry needs a file, so typos fail loudly.
+pub fn load_day(root: &Path, day: u8) -> Result<Vec<Fixture>, Error> {
+    let dir = day_fixtures_dir(root, day);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut manifest = read_manifest(&dir)?;
+    let mut fixtures = Vec::new();
+
+    let entries = fs::read_dir(&dir).map_err(|err| fixture_error(&dir, err.to_string()))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|err| fixture_error(&dir, err.to_string()))?
+            .path();
+
+        if path.extension().and_then(|ext| ext.to_str()) != Some("txt") {
+            continue;
+        }
+
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| fixture_error(&path, "file name should be valid UTF-8"))?
+            .to_string();
+
+        let expected = manifest
+            .remove(&name)
+            .ok_or_else(|| fixture_error(&path, format!("no [{name}] entry in {MANIFEST}")))?;
+
+        let input =
+            fs::read_to_string(&path).map_err(|err| fixture_error(&path, err.to_string()))?;
+
+        fixtures.push(Fixture {
+            day,
+            name,
+            path,
+            input,
+            expected,
+        });
+    }
+
+    if let Some(name) = manifest.keys().next() {
+        return Err(fixture_error(
+            &dir.join(MANIFEST),
+            format!("[{name}] has no matching {name}.txt"),
+        ));
+    }
+
+    fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(fixtures)
+}
+
+/// Loads fixtures from every `day-XX` folder under `tests/fixtures`
+pub fn load_all(root: &Path) -> Result<Vec<Fixture>, Error> {
+    let dir = fixtures_dir(root);
+    let entries = fs::read_dir(&dir).map_err(|err| fixture_error(&dir, err.to_string()))?;
+
+    let mut days = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|err| fixture_error(&dir, err.to_string()))?
+            .path();
+
+        let day = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("day-"))
+            .and_then(|day| day.parse::<u8>().ok());
+
+        match day {
+            Some(day) if path.is_dir() => days.push(day),
+            _ => return Err(fixture_error(&path, "expected a day-XX folder")),
+        }
+    }
+
+    days.sort();
+
+    let mut fixtures = Vec::new();
+    for day in days {
+        fixtures.extend(load_day(root, day)?);
+    }
+
+    Ok(fixtures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace_root;
+
+    #[test]
+    fn loads_fixtures_with_their_answers() {
+        let fixtures = load_day(workspace_root(), 3).unwrap();
+        let names: Vec<&str> = fixtures.iter().map(|f| f.name.as_str()).collect();
+
+        assert_eq!(names, ["example", "reddit"]);
+        assert_eq!(fixtures[0].expected.part(2), Some("467835"));
+        assert_eq!(fixtures[1].expected.part(2), None);
+    }
+
+    #[test]
+    fn manifest_round_trips() {
+        let manifest: Manifest = toml::from_str(
+            "[example]
+part1 = \"1\"
+
+[edge]
+part2 = \"2\"
+",
+        )
+        .unwrap();
+
+        let written = toml::to_string(&manifest).unwrap();
+        assert_eq!(toml::from_str::<Manifest>(&written).unwrap(), manifest);
+        assert_eq!(manifest["edge"].part(1), None);
+    }
+
+    #[test]
+    fn setting_answers_keeps_the_rest_of_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = "# Worked through by hand
+[example]
+part1 = \"1\" # from the puzzle page
+
+# Not from the puzzle
+[edge]
+part2 = \"2\"
+";
+        fs::write(dir.path().join(MANIFEST), original).unwrap();
+
+        let answer = |part2: &str| Expected {
+            part1: None,
+            part2: Some(part2.to_string()),
+        };
+        set_expected(dir.path(), "shrunk", &Expected::default()).unwrap();
+        set_expected(dir.path(), "example", &answer("3")).unwrap();
+
+        let written = fs::read_to_string(dir.path().join(MANIFEST)).unwrap();
+        assert_eq!(
+            written,
+            "# Worked through by hand
+[example]
+part2 = \"3\"
+
+# Not from the puzzle
+[edge]
+part2 = \"2\"
+
+[shrunk]
+"
+        );
+        assert_eq!(read_manifest(dir.path()).unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,128 @@
+//! Guessing which day an input file belongs to from its shape, for inputs
+//! saved somewhere other than their day folder
+
+use std::fmt;
+
+use common::registry;
+
+use crate::days;
+
+/// Days scoring below this aren't worth mentioning
+pub const MIN_CONFIDENCE: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Match {
+    pub day: u8,
+    /// Between 0 and 1, mostly the share of lines the day's format accepts
+    pub confidence: f64,
+}
+
+impl fmt::Display for Match {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {:02}: {:.0}%", self.day, self.confidence * 100.0)
+    }
+}
+
+/// The days the input plausibly belongs to, most likely first
+pub fn identify(input: &str) -> Vec<Match> {
+    let mut matches: Vec<Match> = days()
+        .iter()
+        .map(|day| Match {
+            day: day.day,
+            confidence: confidence(day.day, input),
+        })
+        .filter(|m| m.confidence >= MIN_CONFIDENCE)
+        .collect();
+
+    matches.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    matches
+}
+
+/// How well the input fits a day's format, from 0 to 1. Only an input one of
+/// the day's registered parsers reads in full is certainly the day's. Anything
+/// else scores by how many of its lines fit, so at most all but one of them.
+pub fn confidence(day: u8, input: &str) -> f64 {
+    let mut parsers = registry::parsers().filter(|parser| parser.day == day);
+    if parsers.any(|parser| (parser.parse)(input).is_ok()) {
+        return 1.0;
+    }
+
+    let lines: Vec<&str> = input.lines().filter(|line| !line.is_empty()).collect();
+    if lines.is_empty() {
+        return 0.0;
+    }
+
+    // Most formats are a line per item, so each line is an input on its own.
+    // Days read only as a whole score nothing here.
+    let fits = share(&lines, |line| {
+        registry::parsers().any(|parser| parser.day == day && (parser.parse)(line).is_ok())
+    });
+
+    let at_most = (lines.len() - 1) as f64 / lines.len() as f64;
+    fits.min(at_most)
+}
+
+fn share(lines: &[&str], accepts: impl Fn(&str) -> bool) -> f64 {
+    lines.iter().filter(|line| accepts(line)).count() as f64 / lines.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::load_day, workspace_root};
+
+    #[test]
+    fn every_example_is_identified_as_its_own_day() {
+        for day in days().iter().map(|day| day.day) {
+            for fixture in load_day(workspace_root(), day).unwrap() {
+                let matches = identify(&fixture.input);
+
+                assert_eq!(
+                    matches.first().map(|m| (m.day, m.confidence)),
+                    Some((day, 1.0)),
+                    "day {day:02} {}: {matches:?}",
+                    fixture.name
+                );
+                assert!(
+                    matches[1..].iter().all(|m| m.confidence < 1.0),
+                    "day {day:02} {}: {matches:?}",
+                    fixture.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn confidence_drops_with_lines_that_dont_fit() {
+        let input = "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45\nnot a history";
+
+        assert_eq!(
+            identify(input),
+            [Match {
+                day: 9,
+                confidence: 0.75
+            }]
+        );
+        assert_eq!(identify(input).first().unwrap().to_string(), "day 09: 75%");
+    }
+
+    #[test]
+    fn registered_parsers_settle_it() {
+        let input = "Time:      7  15   30\nDistance:  9  40  200\n";
+        assert_eq!(confidence(6, input), 1.0);
+        assert_eq!(
+            confidence(6, "Time:      7  15   30\nDistance:  9  40\nTime: 7"),
+            0.0
+        );
+
+        // Lines the day's parser reads on their own count towards a partial match
+        assert_eq!(confidence(2, "Game 1: 3 blue\nGame 2: 4 purple"), 0.5);
+        assert_eq!(confidence(2, "Game 1: 3 blue\nGame 2: 4 red"), 1.0);
+    }
+
+    #[test]
+    fn unrelated_text_matches_nothing() {
+        assert!(identify("Dear Santa,\nI would like a pony.\n").is_empty());
+        assert!(identify("").is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+pub const LEDGER: &str = "submissions.toml";
+
+/// What the site said about a submitted answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Verdict {
+    Accepted,
+    TooHigh,
+    TooLow,
+    /// Rejected without a hint, as happens for non-numeric answers
+    Wrong,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Accepted => write!(f, "accepted"),
+            Verdict::TooHigh => write!(f, "too high"),
+            Verdict::TooLow => write!(f, "too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rejected {
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+/// Everything submitted for one part
+///
+/// ```toml
+/// [day-05.part2]
+/// accepted = "46"
+///
+/// [[day-05.part2.rejected]]
+/// answer = "52"
+/// verdict = "too-high"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submissions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accepted: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rejected: Vec<Rejected>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// The answer was submitted before and rejected
+    Rejected(Verdict),
+    /// The answer is at or below an answer that was too low
+    TooLow(i128),
+    /// The answer is at or above an answer that was too high
+    TooHigh(i128),
+    /// The part was already solved with a different answer
+    NotAccepted(String),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::Rejected(verdict) => write!(f, "already rejected ({verdict})"),
+            Warning::TooLow(bound) => write!(f, "too low, {bound} was already rejected as too low"),
+            Warning::TooHigh(bound) => {
+                write!(f, "too high, {bound} was already rejected as too high")
+            }
+            Warning::NotAccepted(accepted) => write!(f, "differs from accepted answer {accepted}"),
+        }
+    }
+}
+
+impl Submissions {
+    fn bound(&self, verdict: Verdict) -> impl Iterator<Item = i128> + '_ {
+        self.rejected
+            .iter()
+            .filter(move |rejected| rejected.verdict == verdict)
+            .filter_map(|rejected| rejected.answer.trim().parse().ok())
+    }
+
+    /// Checks a freshly computed answer against what we already know
+    pub fn check(&self, answer: &str) -> Option<Warning> {
+        if let Some(accepted) = &self.accepted {
+            return (accepted != answer).then(|| Warning::NotAccepted(accepted.clone()));
+        }
+
+        if let Some(rejected) = self.rejected.iter().find(|r| r.answer == answer) {
+            return Some(Warning::Rejected(rejected.verdict));
+        }
+
+        let value: i128 = answer.trim().parse().ok()?;
+        if let Some(low) = self
+            .bound(Verdict::TooLow)
+            .max()
+            .filter(|&low| value <= low)
+        {
+            return Some(Warning::TooLow(low));
+        }
+        if let Some(high) = self
+            .bound(Verdict::TooHigh)
+            .min()
+            .filter(|&high| value >= high)
+        {
+            return Some(Warning::TooHigh(high));
+        }
+
+        None
+    }
+
+    pub fn record(&mut self, answer: &str, verdict: Verdict) {
+        match verdict {
+            Verdict::Accepted => self.accepted = Some(answer.to_string()),
+            _ => {
+                self.rejected.retain(|r| r.answer != answer);
+                self.rejected.push(Rejected {
+                    answer: answer.to_string(),
+                    verdict,
+                });
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaySubmissions {
+    #[serde(default, skip_serializing_if = "is_empty")]
+    pub part1: Submissions,
+    #[serde(default, skip_serializing_if = "is_empty")]
+    pub part2: Submissions,
+}
+
+fn is_empty(submissions: &Submissions) -> bool {
+    *submissions == Submissions::default()
+}
+
+/// Answers we've submitted by hand, keyed by `day-XX`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Ledger(BTreeMap<String, DaySubmissions>);
+
+fn key(day: u8) -> String {
+    format!("day-{day:02}")
+}
+
+impl Ledger {
+    pub fn part(&self, day: u8, part: u8) -> Option<&Submissions> {
+        let day = self.0.get(&key(day))?;
+        match part {
+            1 => Some(&day.part1),
+            2 => Some(&day.part2),
+            _ => None,
+        }
+    }
+
+    pub fn check(&self, day: u8, part: u8, answer: &str) -> Option<Warning> {
+        self.part(day, part)?.check(answer)
+    }
+
+    pub fn record(
+        &mut self,
+        day: u8,
+        part: u8,
+        answer: &str,
+        verdict: Verdict,
+    ) -> Result<(), Error> {
+        let submissions = self.0.entry(key(day)).or_default();
+        let submissions = match part {
+            1 => &mut submissions.part1,
+            2 => &mut submissions.part2,
+            _ => return Err(Error::UnknownPart(part)),
+        };
+
+        submissions.record(answer, verdict);
+        Ok(())
+    }
+}
+
+pub fn ledger_path(root: &Path) -> PathBuf {
+    root.join(LEDGER)
+}
+
+fn ledger_error(path: &Path, message: impl Into<String>) -> Error {
+    Error::Ledger {
+        path: path.to_path_buf(),
+        message: message.into(),
+    }
+}
+
+pub fn read_ledger(root: &Path) -> Result<Ledger, Error> {
+    let path = ledger_path(root);
+    if !path.exists() {
+        return Ok(Ledger::default());
+    }
+
+    let contents = fs::read_to_string(&path).map_err(|err| ledger_error(&path, err.to_string()))?;
+    toml::from_str(&contents).map_err(|err| ledger_error(&path, err.to_string()))
+}
+
+pub fn write_ledger(root: &Path, ledger: &Ledger) -> Result<(), Error> {
+    let path = ledger_path(root);
+    let contents = toml::to_string(ledger).map_err(|err| ledger_error(&path, err.to_string()))?;
+    fs::write(&path, contents).map_err(|err| ledger_error(&path, err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submissions() -> Submissions {
+        let mut submissions = Submissions::default();
+        submissions.record("52", Verdict::TooHigh);
+        submissions.record("60", Verdict::TooHigh);
+        submissions.record("30", Verdict::TooLow);
+        submissions.record("41", Verdict::Wrong);
+        submissions
+    }
+
+    #[test]
+    fn warns_about_known_rejections_and_bounds() {
+        let submissions = submissions();
+
+        assert_eq!(
+            submissions.check("41"),
+            Some(Warning::Rejected(Verdict::Wrong))
+        );
+        assert_eq!(submissions.check("55"), Some(Warning::TooHigh(52)));
+        assert_eq!(submissions.check("12"), Some(Warning::TooLow(30)));
+        assert_eq!(submissions.check("46"), None);
+        assert_eq!(submissions.check("not a number"), None);
+    }
+
+    #[test]
+    fn accepted_answer_wins() {
+        let mut submissions = submissions();
+        submissions.record("46", Verdict::Accepted);
+
+        assert_eq!(submissions.check("46"), None);
+        assert_eq!(
+            submissions.check("45"),
+            Some(Warning::NotAccepted("46".to_string()))
+        );
+    }
+
+    #[test]
+    fn ledger_round_trips() {
+        let mut ledger = Ledger::default();
+        ledger.record(5, 2, "52", Verdict::TooHigh).unwrap();
+        ledger.record(5, 1, "35", Verdict::Accepted).unwrap();
+
+        let written = toml::to_string(&ledger).unwrap();
+        assert_eq!(toml::from_str::<Ledger>(&written).unwrap(), ledger);
+        assert_eq!(ledger.check(5, 2, "60"), Some(Warning::TooHigh(52)));
+        assert_eq!(ledger.check(6, 1, "60"), None);
+    }
+}
@@ -0,0 +1,660 @@
+pub mod alloc;
+pub mod compare;
+pub mod extract;
+pub mod fetch;
+pub mod fixtures;
+pub mod identify;
+pub mod ledger;
+pub mod outcome;
+pub mod plugin;
+pub mod report;
+pub mod serve;
+pub mod shrink;
+pub mod tui;
+pub mod visualise;
+pub mod watch;
+
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs, io,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{mpsc, LazyLock, Once},
+    thread,
+    time::{Duration, Instant},
+};
+
+use alloc::AllocStats;
+use common::{
+    budget::{self, Budget, Cancelled, Reason},
+    registry,
+};
+
+pub type Solver = fn(&str) -> common::Result<String>;
+
+/// Name of the solvers in [`Day::parts`], for `--impl` and `aoc compare`
+pub const DEFAULT_IMPL: &str = registry::DEFAULT;
+
+/// Another way of solving a part, kept to check the main solver against
+pub struct Alternative {
+    pub name: &'static str,
+    pub part: u8,
+    pub solver: Solver,
+}
+
+pub struct Day {
+    pub day: u8,
+    pub parts: [Solver; 2],
+    pub alternatives: &'static [Alternative],
+}
+
+impl Day {
+    /// Every solver for a part, starting with the default
+    pub fn implementations(&self, part: u8) -> Result<Vec<(&'static str, Solver)>, Error> {
+        let solver = match part {
+            1 | 2 => self.parts[part as usize - 1],
+            _ => return Err(Error::UnknownPart(part)),
+        };
+
+        let alternatives = self
+            .alternatives
+            .iter()
+            .filter(|alternative| alternative.part == part)
+            .map(|alternative| (alternative.name, alternative.solver));
+
+        Ok([(DEFAULT_IMPL, solver)]
+            .into_iter()
+            .chain(alternatives)
+            .collect())
+    }
+
+    /// The named solver for a part. A name the day only has for the other part
+    /// falls back to the default, so `--impl` can be given for a whole day.
+    pub fn solver(&self, part: u8, name: Option<&str>) -> Result<Solver, Error> {
+        let implementations = self.implementations(part)?;
+        let name = name.unwrap_or(DEFAULT_IMPL);
+
+        if let Some((_, solver)) = implementations.iter().find(|(n, _)| *n == name) {
+            return Ok(*solver);
+        }
+
+        if self
+            .alternatives
+            .iter()
+            .any(|alternative| alternative.name == name)
+        {
+            return Ok(implementations[0].1);
+        }
+
+        Err(Error::UnknownImpl {
+            day: self.day,
+            name: name.to_string(),
+        })
+    }
+}
+
+// Stands in for a part no solver was registered for
+fn unregistered(_: &str) -> common::Result<String> {
+    Err(common::Error::Invalid(
+        "no solver is registered for this part".to_string(),
+    ))
+}
+
+// The linker leaves out crates that nothing refers to, and their registrations
+// with them, so the build script names every day crate this one depends on
+include!(concat!(env!("OUT_DIR"), "/linked.rs"));
+
+// The day crates register their solvers with `#[aoc(day, part)]`, so a new
+// day only needs adding to this crate's dependencies
+static REGISTERED: LazyLock<Vec<Day>> = LazyLock::new(|| {
+    let mut days: BTreeMap<u8, Day> = BTreeMap::new();
+    let mut alternatives: BTreeMap<u8, Vec<Alternative>> = BTreeMap::new();
+    let mut seen = BTreeSet::new();
+
+    for solution in registry::solutions() {
+        // Which of two registrations won would be down to the linker
+        assert!(
+            seen.insert((solution.day, solution.part, solution.name)),
+            "day {} part {} has two solvers named {:?}",
+            solution.day,
+            solution.part,
+            solution.name
+        );
+
+        let day = days.entry(solution.day).or_insert_with(|| Day {
+            day: solution.day,
+            parts: [unregistered, unregistered],
+            alternatives: &[],
+        });
+
+        if solution.name == DEFAULT_IMPL {
+            day.parts[solution.part as usize - 1] = solution.solver;
+        } else {
+            alternatives
+                .entry(solution.day)
+                .or_default()
+                .push(Alternative {
+                    name: solution.name,
+                    part: solution.part,
+                    solver: solution.solver,
+                });
+        }
+    }
+
+    // Registration order depends on the linker, so sort for stable output
+    for (day, mut alternatives) in alternatives {
+        alternatives.sort_by_key(|alternative| (alternative.part, alternative.name));
+        days.get_mut(&day)
+            .expect("alternatives belong to a registered day")
+            .alternatives = alternatives.leak();
+    }
+
+    days.into_values().collect()
+});
+
+/// Every registered day, in order
+pub fn days() -> &'static [Day] {
+    &REGISTERED
+}
+
+pub fn find_day(day: u8) -> Result<&'static Day, Error> {
+    days()
+        .iter()
+        .find(|d| d.day == day)
+        .ok_or(Error::UnknownDay(day))
+}
+
+#[derive(Debug)]
+pub enum Error {
+    UnknownDay(u8),
+    UnknownPart(u8),
+    UnknownImpl {
+        day: u8,
+        name: String,
+    },
+    Input {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Output {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Fixture {
+        path: PathBuf,
+        message: String,
+    },
+    Ledger {
+        path: PathBuf,
+        message: String,
+    },
+    Solver {
+        day: u8,
+        part: u8,
+        source: common::Error,
+    },
+    Panic {
+        day: u8,
+        part: u8,
+        message: String,
+    },
+    /// The solver was still running when its timeout expired. `steps` is
+    /// `None` if it never checked its budget, and had to be abandoned.
+    TimedOut {
+        day: u8,
+        part: u8,
+        after: Duration,
+        steps: Option<u64>,
+    },
+    OutOfSteps {
+        day: u8,
+        part: u8,
+        steps: u64,
+    },
+    Fetch(String),
+    Extract(String),
+    Serve(String),
+    Shrink(String),
+    Tui(String),
+    Watch(String),
+    Plugin(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownDay(day) => write!(f, "no solutions registered for day {day}"),
+            Error::UnknownPart(part) => write!(f, "part should be 1 or 2, got {part}"),
+            Error::UnknownImpl { day, name } => {
+                write!(f, "day {day:02} has no implementation called {name}")
+            }
+            Error::Input { path, source } => {
+                write!(f, "could not read input {}: {source}", path.display())
+            }
+            Error::Output { path, source } => {
+                write!(f, "could not write {}: {source}", path.display())
+            }
+            Error::Fixture { path, message } => {
+                write!(f, "bad fixture {}: {message}", path.display())
+            }
+            Error::Ledger { path, message } => {
+                write!(f, "bad ledger {}: {message}", path.display())
+            }
+            Error::Solver { day, part, source } => {
+                write!(f, "day {day:02} part {part} failed: {source}")
+            }
+            Error::Panic { day, part, message } => {
+                write!(f, "day {day:02} part {part} panicked: {message}")
+            }
+            Error::TimedOut {
+                day,
+                part,
+                after,
+                steps,
+            } => {
+                write!(f, "day {day:02} part {part} timed out after {after:.2?}")?;
+                match steps {
+                    Some(steps) => write!(f, " and {steps} steps"),
+                    None => write!(f, " without checking its budget"),
+                }
+            }
+            Error::OutOfSteps { day, part, steps } => {
+                write!(f, "day {day:02} part {part} ran out of steps after {steps}")
+            }
+            Error::Fetch(message) => write!(f, "fetch failed: {message}"),
+            Error::Extract(message) => write!(f, "could not extract examples: {message}"),
+            Error::Serve(message) => write!(f, "server failed: {message}"),
+            Error::Shrink(message) => write!(f, "could not shrink: {message}"),
+            Error::Tui(message) => write!(f, "terminal UI failed: {message}"),
+            Error::Watch(message) => write!(f, "watch failed: {message}"),
+            Error::Plugin(message) => write!(f, "plugin failed: {message}"),
+        }
+    }
+}
+
+impl Error {
+    /// Whether the solver itself failed, rather than the runner around it
+    pub fn is_solver_failure(&self) -> bool {
+        matches!(
+            self,
+            Error::Solver { .. }
+                | Error::Panic { .. }
+                | Error::TimedOut { .. }
+                | Error::OutOfSteps { .. }
+        )
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Input { source, .. } => Some(source),
+            Error::Output { source, .. } => Some(source),
+            Error::Solver { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+// The solutions folder, which holds one `day-XX` crate per puzzle
+pub fn workspace_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("runner crate should live inside the workspace")
+}
+
+pub fn day_dir(root: &Path, day: u8) -> PathBuf {
+    root.join(format!("day-{day:02}"))
+}
+
+pub fn input_path(root: &Path, day: u8) -> PathBuf {
+    day_dir(root, day).join("input.txt")
+}
+
+pub fn read_input(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|source| Error::Input {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct Answer {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub elapsed: Duration,
+    pub allocations: Option<AllocStats>,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {:02} part {}: {} ({:.2?})",
+            self.day, self.part, self.answer, self.elapsed
+        )?;
+
+        if let Some(stats) = &self.allocations {
+            write!(f, " [{stats}]")?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Count heap allocations made by the solver (needs the binary to install
+    /// [`alloc::CountingAllocator`])
+    pub profile_alloc: bool,
+    /// Wall-clock limit for each part
+    pub timeout: Option<Duration>,
+    /// Limit on the loop iterations a part may count with [`budget::tick`]
+    pub max_steps: Option<u64>,
+    /// Which of the day's implementations to run, if not the default
+    pub implementation: Option<String>,
+    /// Keep the solver's panic message off stderr. It's still reported in
+    /// [`Error::Panic`]
+    pub quiet_panics: bool,
+}
+
+thread_local! {
+    // Set on solver threads whose panics shouldn't be printed
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+// Installs, once for the process, a panic hook that says nothing on quiet
+// threads and hands every other panic to the hook that was there before. Only
+// this thread is made quiet.
+fn quiet_panics_on_this_thread() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.get() {
+                previous(info);
+            }
+        }));
+    });
+
+    QUIET.set(true);
+}
+
+// How long a cancelled solver gets to notice its token before it's abandoned
+const GRACE: Duration = Duration::from_secs(1);
+
+// Deep recursion on a big grid shouldn't overflow before the budget kicks in
+const WORKER_STACK: usize = 256 * 1024 * 1024;
+
+type Finished = (thread::Result<common::Result<String>>, Option<AllocStats>);
+
+/// Runs one part on a worker thread, within the limits set in `options`.
+/// Panics and cancellations are reported as errors rather than unwinding into
+/// the caller.
+pub fn solve(day: &Day, part: u8, input: &str, options: &RunOptions) -> Result<Answer, Error> {
+    solve_holding(day, part, input, options, ())
+}
+
+/// [`solve`], keeping `held` alive on the solver's thread until the solver
+/// finishes, even after it's been abandoned for timing out without ticking
+pub fn solve_holding(
+    day: &Day,
+    part: u8,
+    input: &str,
+    options: &RunOptions,
+    held: impl Send + 'static,
+) -> Result<Answer, Error> {
+    let solver = day.solver(part, options.implementation.as_deref())?;
+
+    #[cfg(feature = "tracing")]
+    let span = tracing::info_span!("solve", day = day.day, part);
+
+    let budget = Budget {
+        token: budget::CancelToken::new(),
+        max_steps: options.max_steps,
+    };
+    let token = budget.token.clone();
+    let profile_alloc = options.profile_alloc;
+    let quiet_panics = options.quiet_panics;
+    let input = input.to_string();
+
+    let (tx, rx) = mpsc::channel::<Finished>();
+    let start = Instant::now();
+
+    thread::Builder::new()
+        .name(format!("day-{:02}-part-{part}", day.day))
+        .stack_size(WORKER_STACK)
+        .spawn(move || {
+            let _held = held;
+            #[cfg(feature = "tracing")]
+            let _span = span.entered();
+
+            if quiet_panics {
+                quiet_panics_on_this_thread();
+            }
+
+            // Most days panic on input they don't expect, which shouldn't take
+            // the runner down
+            let run = || {
+                budget::with_budget(budget, || {
+                    panic::catch_unwind(AssertUnwindSafe(|| solver(&input)))
+                })
+            };
+
+            let finished = if profile_alloc {
+                let (result, stats) = alloc::measure(run);
+                (result, Some(stats))
+            } else {
+                (run(), None)
+            };
+
+            // The runner may have given up waiting already
+            let _ = tx.send(finished);
+        })
+        .expect("should be able to spawn a solver thread");
+
+    let received = match options.timeout {
+        None => rx.recv().ok(),
+        Some(timeout) => match rx.recv_timeout(timeout) {
+            Ok(finished) => Some(finished),
+            Err(_) => {
+                token.cancel();
+                match rx.recv_timeout(GRACE) {
+                    Ok(finished) => Some(finished),
+                    // Nothing can stop a thread that never ticks, so leave it behind
+                    Err(_) => {
+                        return Err(Error::TimedOut {
+                            day: day.day,
+                            part,
+                            after: timeout,
+                            steps: None,
+                        })
+                    }
+                }
+            }
+        },
+    };
+
+    let elapsed = start.elapsed();
+    let (result, allocations) = received.expect("solver thread should report back");
+
+    let answer = result
+        .map_err(|payload| match payload.downcast::<Cancelled>() {
+            Ok(cancelled) => match cancelled.reason {
+                Reason::Cancelled => Error::TimedOut {
+                    day: day.day,
+                    part,
+                    after: options.timeout.unwrap_or(elapsed),
+                    steps: Some(cancelled.steps),
+                },
+                Reason::OutOfSteps => Error::OutOfSteps {
+                    day: day.day,
+                    part,
+                    steps: cancelled.steps,
+                },
+            },
+            Err(payload) => Error::Panic {
+                day: day.day,
+                part,
+                message: panic_message(payload.as_ref()),
+            },
+        })?
+        .map_err(|source| Error::Solver {
+            day: day.day,
+            part,
+            source,
+        })?;
+
+    Ok(Answer {
+        day: day.day,
+        part,
+        answer,
+        elapsed,
+        allocations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_day_is_registered_in_order() {
+        let manifest = fs::read_to_string(workspace_root().join("Cargo.toml")).unwrap();
+        let manifest: toml::Table = manifest.parse().unwrap();
+        let mut members: Vec<u8> = manifest["workspace"]["members"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|member| member.as_str()?.strip_prefix("day-")?.parse().ok())
+            .collect();
+        members.sort();
+
+        let days: Vec<u8> = days().iter().map(|d| d.day).collect();
+        assert_eq!(days, members);
+    }
+
+    #[test]
+    fn solve_runs_the_requested_part() {
+        let day = find_day(6).unwrap();
+        let input = "Time:      7  15   30
+Distance:  9  40  200";
+
+        let options = RunOptions::default();
+
+        assert_eq!(solve(day, 1, input, &options).unwrap().answer, "288");
+        assert_eq!(solve(day, 2, input, &options).unwrap().answer, "71503");
+        assert!(matches!(
+            solve(day, 3, input, &options),
+            Err(Error::UnknownPart(3))
+        ));
+    }
+
+    #[test]
+    fn solver_panics_become_errors() {
+        let day = find_day(6).unwrap();
+        let result = solve(day, 1, "Time: 7", &RunOptions::default());
+
+        assert!(matches!(
+            result,
+            Err(Error::Panic {
+                day: 6,
+                part: 1,
+                ..
+            })
+        ));
+    }
+
+    const SPINNING: Day = Day {
+        day: 99,
+        parts: [
+            |_| loop {
+                budget::tick();
+            },
+            |_| loop {
+                thread::sleep(Duration::from_millis(10));
+            },
+        ],
+        alternatives: &[],
+    };
+
+    #[test]
+    fn cooperative_solvers_stop_at_the_timeout() {
+        let options = RunOptions {
+            timeout: Some(Duration::from_millis(50)),
+            ..RunOptions::default()
+        };
+
+        assert!(matches!(
+            solve(&SPINNING, 1, "", &options),
+            Err(Error::TimedOut {
+                steps: Some(steps),
+                ..
+            }) if steps > 0
+        ));
+        assert!(matches!(
+            solve(&SPINNING, 2, "", &options),
+            Err(Error::TimedOut { steps: None, .. })
+        ));
+    }
+
+    #[test]
+    fn solvers_stop_when_out_of_steps() {
+        let options = RunOptions {
+            max_steps: Some(1000),
+            ..RunOptions::default()
+        };
+
+        assert!(matches!(
+            solve(&SPINNING, 1, "", &options),
+            Err(Error::OutOfSteps {
+                day: 99,
+                part: 1,
+                steps: 1001
+            })
+        ));
+    }
+
+    #[test]
+    fn picks_implementations_by_name() {
+        let day = find_day(6).unwrap();
+        let names: Vec<&str> = day
+            .implementations(2)
+            .unwrap()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, [DEFAULT_IMPL, "loop"]);
+
+        let options = RunOptions {
+            implementation: Some("loop".to_string()),
+            ..RunOptions::default()
+        };
+        let input = "Time:      7  15   30
+Distance:  9  40  200";
+        assert_eq!(solve(day, 1, input, &options).unwrap().answer, "288");
+
+        // Day 10 only has an alternative part 2, so part 1 keeps its default
+        let day = find_day(10).unwrap();
+        assert!(day.solver(1, Some("flood-fill")).is_ok());
+        assert!(matches!(
+            day.solver(2, Some("quantum")),
+            Err(Error::UnknownImpl { day: 10, .. })
+        ));
+    }
+
+    #[test]
+    fn unknown_day_is_an_error() {
+        assert!(matches!(find_day(26), Err(Error::UnknownDay(26))));
+    }
+}
@@ -0,0 +1,564 @@
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Duration,
+};
+
+#[cfg(feature = "alloc")]
+use aoc::alloc::CountingAllocator;
+use aoc::{
+    compare::compare,
+    extract::{extract, write_fixtures},
+    fetch::{session_token, Fetched, Fetcher},
+    find_day, fixtures,
+    identify::identify,
+    input_path,
+    ledger::{read_ledger, write_ledger, Verdict},
+    outcome::run_day,
+    read_input,
+    report::write_report,
+    serve::{bind, serve, ServeOptions},
+    shrink::{shrink, write_fixture},
+    tui::{self, simulation, Stepper},
+    watch::watch,
+    workspace_root, Error, RunOptions,
+};
+use clap::{Parser, Subcommand};
+
+#[cfg(feature = "alloc")]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Run the Advent of Code 2023 solutions")]
+struct Cli {
+    /// Tracing filter for solution spans and events, e.g. `debug` or `day_05=trace`
+    #[cfg(feature = "tracing")]
+    #[arg(long, global = true)]
+    log: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run solutions against the puzzle input (every day with an input.txt if no day is given)
+    Run {
+        day: Option<u8>,
+
+        /// Only run this part
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+
+        /// Read the puzzle input from this file instead of day-XX/input.txt
+        #[arg(short, long, requires = "day")]
+        input: Option<PathBuf>,
+
+        /// Report allocations, bytes allocated and peak heap for each part
+        #[cfg(feature = "alloc")]
+        #[arg(long)]
+        alloc: bool,
+
+        /// Also check the day's example fixtures against their expected answers
+        #[arg(long)]
+        examples: bool,
+
+        /// Print one JSON object per result instead of text
+        #[arg(long)]
+        json: bool,
+
+        /// Give up on a part after this many seconds (0 waits forever)
+        #[arg(long, default_value_t = 60.0, value_parser = parse_seconds)]
+        timeout: f64,
+
+        /// Give up on a part after this many loop iterations
+        #[arg(long)]
+        max_steps: Option<u64>,
+
+        /// Run this implementation instead of the default, where the day has one
+        #[arg(long = "impl")]
+        implementation: Option<String>,
+    },
+    /// Run every implementation of a day, check they agree and compare their timings
+    Compare {
+        day: u8,
+
+        /// Only compare this part
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+
+        /// Read the puzzle input from this file instead of day-XX/input.txt. Without
+        /// either, the examples are compared
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// Time each implementation this many times, keeping the fastest
+        #[arg(long, default_value_t = 1)]
+        runs: usize,
+
+        /// Give up on an implementation after this many seconds (0 waits forever)
+        #[arg(long, default_value_t = 60.0, value_parser = parse_seconds)]
+        timeout: f64,
+    },
+    /// Cut down an input that crashes a part or makes its implementations disagree,
+    /// and save what's left as a fixture
+    Shrink {
+        day: u8,
+
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+
+        /// The failing input
+        input: PathBuf,
+
+        /// Give up on a candidate after this many seconds (0 waits forever)
+        #[arg(long, default_value_t = 10.0, value_parser = parse_seconds)]
+        timeout: f64,
+    },
+    /// Write an HTML page of every day's answers, timings and example results
+    Report {
+        /// Folder for index.html and the day pictures
+        #[arg(long)]
+        html: PathBuf,
+
+        /// Give up on a part after this many seconds (0 waits forever)
+        #[arg(long, default_value_t = 60.0, value_parser = parse_seconds)]
+        timeout: f64,
+    },
+    /// Answer `POST /day/{n}/part/{p}` requests with the puzzle input as the body
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:3000")]
+        addr: String,
+
+        /// Reject inputs over this many bytes
+        #[arg(long, default_value_t = 1024 * 1024)]
+        max_body: u64,
+
+        /// Give up on a request after this many seconds (0 waits forever)
+        #[arg(long, default_value_t = 10.0, value_parser = parse_seconds)]
+        timeout: f64,
+
+        /// Turn away requests while this many are being solved
+        #[arg(long, default_value_t = 4)]
+        max_solvers: usize,
+    },
+    /// Download a day's puzzle input to day-XX/input.txt, if it isn't there already
+    Fetch { day: u8 },
+    /// Turn the examples on a saved puzzle page into fixtures for the day
+    Extract {
+        day: u8,
+
+        /// The puzzle page, saved from the browser as HTML
+        page: PathBuf,
+
+        /// Replace fixtures that already exist
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Record what the site said about a submitted answer, so `run` can warn
+    /// before the same or a worse answer is submitted again
+    Record {
+        day: u8,
+
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+
+        answer: String,
+
+        verdict: Verdict,
+    },
+    /// Guess which days an input file belongs to from its format
+    Identify {
+        file: PathBuf,
+
+        /// Also run the solutions of every day it could belong to
+        #[arg(long)]
+        run: bool,
+
+        /// Give up on a part after this many seconds (0 waits forever)
+        #[arg(long, default_value_t = 60.0, value_parser = parse_seconds)]
+        timeout: f64,
+    },
+    /// Step through a day's puzzle in the terminal (days 3, 8 and 10)
+    Tui {
+        day: u8,
+
+        /// Read the puzzle input from this file instead of day-XX/input.txt. Without
+        /// either, the first example is used
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+    /// Rerun a day's examples and input whenever its code, input or fixtures change
+    Watch {
+        day: u8,
+
+        /// Rebuild only the day, as a dynamic library, and reload it in place
+        /// instead of rebuilding the whole runner
+        #[arg(long)]
+        plugin: bool,
+    },
+}
+
+#[cfg(feature = "tracing")]
+fn init_tracing(filter: &str) {
+    use aoc::alloc::Uncounted;
+    use tracing_subscriber::{fmt::format::FmtSpan, util::SubscriberInitExt, EnvFilter};
+
+    // Closing spans report their busy/idle time, which is what we want to see.
+    // Logging runs on the solver's thread, so keep it out of `--alloc`
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new(filter))
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
+        .finish();
+    Uncounted(subscriber).init();
+}
+
+struct RunArgs {
+    day: Option<u8>,
+    part: Option<u8>,
+    input: Option<PathBuf>,
+    examples: bool,
+    json: bool,
+    options: RunOptions,
+}
+
+// Returns whether every part ran and every example matched
+fn run(args: RunArgs) -> Result<bool, Error> {
+    let parts = args.part.map_or(vec![1, 2], |p| vec![p]);
+    let root = workspace_root();
+
+    let days = match args.day {
+        Some(day) => vec![find_day(day)?],
+        None => aoc::days().iter().collect(),
+    };
+
+    let mut passed = true;
+    for solutions in days {
+        let path = args
+            .input
+            .clone()
+            .unwrap_or_else(|| input_path(root, solutions.day));
+
+        // When running everything, or just checking examples, skip the days we
+        // haven't downloaded input for
+        let optional = args.day.is_none() || (args.examples && args.input.is_none());
+        let input = if optional && !path.exists() {
+            eprintln!(
+                "day {:02}: skipping, no input at {}",
+                solutions.day,
+                path.display()
+            );
+            None
+        } else {
+            Some(path.as_path())
+        };
+
+        if input.is_none() && !args.examples {
+            continue;
+        }
+
+        let outcomes = run_day(root, solutions, &parts, input, args.examples, &args.options)?;
+        for outcome in outcomes {
+            passed &= outcome.passed();
+            if args.json {
+                // Someone piping the JSON should still see a clash with the ledger
+                if let Some(warning) = &outcome.warning {
+                    eprintln!(
+                        "warning: day {:02} part {}: {warning}",
+                        outcome.day, outcome.part
+                    );
+                }
+                let line = serde_json::to_string(&outcome).expect("outcomes should serialize");
+                println!("{line}");
+            } else {
+                println!("{outcome}");
+            }
+        }
+    }
+
+    Ok(passed)
+}
+
+// Rejects anything `timeout_from_secs` can't turn into a `Duration`
+fn parse_seconds(arg: &str) -> Result<f64, String> {
+    let seconds: f64 = arg.parse().map_err(|err| format!("{err}"))?;
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|_| format!("{arg} isn't a number of seconds from 0 to {}", u64::MAX))?;
+    Ok(seconds)
+}
+
+fn timeout_from_secs(seconds: f64) -> Option<Duration> {
+    // `parse_seconds` has already checked it converts
+    Duration::try_from_secs_f64(seconds)
+        .ok()
+        .filter(|_| seconds > 0.0)
+}
+
+fn compare_day(
+    day: u8,
+    part: Option<u8>,
+    input: Option<PathBuf>,
+    runs: usize,
+    timeout: Option<Duration>,
+) -> Result<bool, Error> {
+    let solutions = find_day(day)?;
+    let root = workspace_root();
+    let parts = part.map_or(vec![1, 2], |p| vec![p]);
+
+    let input = input.or_else(|| Some(input_path(root, day)).filter(|path| path.exists()));
+    let options = RunOptions {
+        timeout,
+        ..RunOptions::default()
+    };
+
+    let comparisons = compare(root, solutions, &parts, input.as_deref(), runs, &options)?;
+    for comparison in &comparisons {
+        print!("{comparison}");
+    }
+
+    Ok(comparisons.iter().all(|comparison| comparison.agree()))
+}
+
+fn shrink_input(day: u8, part: u8, input: &Path, timeout: Option<Duration>) -> Result<(), Error> {
+    let solutions = find_day(day)?;
+    let options = RunOptions {
+        timeout,
+        ..RunOptions::default()
+    };
+
+    let shrunk = shrink(solutions, part, &read_input(input)?, &options)?;
+    println!(
+        "day {day:02} part {part}: {}, shrunk from {} to {} lines in {} runs",
+        shrunk.failure, shrunk.lines_before, shrunk.lines_after, shrunk.runs
+    );
+
+    let name = write_fixture(workspace_root(), day, &shrunk.input)?;
+    println!(
+        "wrote tests/fixtures/day-{day:02}/{name}.txt, add its expected answers to answers.toml"
+    );
+    Ok(())
+}
+
+fn report(out: &Path, timeout: Option<Duration>) -> Result<(), Error> {
+    let options = RunOptions {
+        timeout,
+        ..RunOptions::default()
+    };
+
+    let index = write_report(workspace_root(), out, &options)?;
+    println!("wrote {}", index.display());
+    Ok(())
+}
+
+fn serve_api(
+    addr: &str,
+    max_body: u64,
+    timeout: Option<Duration>,
+    max_solvers: usize,
+) -> Result<(), Error> {
+    let server = bind(addr)?;
+    println!("listening on http://{}", server.server_addr());
+
+    serve(
+        server,
+        ServeOptions {
+            max_body,
+            timeout,
+            max_solvers,
+            ..ServeOptions::default()
+        },
+    );
+    Ok(())
+}
+
+fn fetch(day: u8) -> Result<(), Error> {
+    find_day(day)?;
+
+    let root = workspace_root();
+    match Fetcher::new(root).fetch(root, day, session_token)? {
+        Fetched::Cached(path) => println!("day {day:02}: already have {}", path.display()),
+        Fetched::Downloaded(path) => println!("day {day:02}: saved {}", path.display()),
+    }
+
+    Ok(())
+}
+
+fn extract_examples(day: u8, page: &Path, overwrite: bool) -> Result<(), Error> {
+    let html = read_input(page)?;
+    let examples = extract(&html)?;
+    let names = write_fixtures(workspace_root(), day, &examples, overwrite)?;
+
+    for (name, example) in names.iter().zip(&examples) {
+        let parts: Vec<String> = [1, 2]
+            .into_iter()
+            .filter_map(|part| Some(format!("part {part} = {}", example.expected.part(part)?)))
+            .collect();
+        println!("day {day:02}: wrote {name}.txt ({})", parts.join(", "));
+    }
+
+    Ok(())
+}
+
+fn identify_file(file: &Path, run: bool, timeout: Option<Duration>) -> Result<bool, Error> {
+    let matches = identify(&read_input(file)?);
+    if matches.is_empty() {
+        println!("{} doesn't look like any day's input", file.display());
+        return Ok(false);
+    }
+
+    for m in &matches {
+        println!("{m}");
+    }
+
+    if !run {
+        return Ok(true);
+    }
+
+    let options = RunOptions {
+        timeout,
+        ..RunOptions::default()
+    };
+    for m in &matches {
+        let outcomes = run_day(
+            workspace_root(),
+            find_day(m.day)?,
+            &[1, 2],
+            Some(file),
+            false,
+            &options,
+        )?;
+        for outcome in outcomes {
+            println!("{outcome}");
+        }
+    }
+
+    Ok(true)
+}
+
+fn step_through(day: u8, input: Option<PathBuf>) -> Result<(), Error> {
+    find_day(day)?;
+
+    // Only a missing day-XX/input.txt falls back to the example, not a typo
+    // in --input
+    let root = workspace_root();
+    let input = match input {
+        Some(path) => read_input(&path)?,
+        None => {
+            let path = input_path(root, day);
+            match fixtures::load_day(root, day)?.into_iter().next() {
+                Some(fixture) if !path.exists() => fixture.input,
+                _ => read_input(&path)?,
+            }
+        }
+    };
+
+    let simulation = simulation(day, &input)
+        .ok_or_else(|| Error::Tui(format!("day {day:02} has nothing to step through")))?;
+
+    let mut terminal = ratatui::init();
+    let result = tui::run(&mut terminal, Stepper::new(simulation));
+    ratatui::restore();
+
+    result.map_err(|err| Error::Tui(err.to_string()))
+}
+
+fn record(day: u8, part: u8, answer: &str, verdict: Verdict) -> Result<(), Error> {
+    find_day(day)?;
+
+    let root = workspace_root();
+    let mut ledger = read_ledger(root)?;
+    ledger.record(day, part, answer, verdict)?;
+    write_ledger(root, &ledger)?;
+
+    println!("day {day:02} part {part}: recorded {answer} as {verdict}");
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    #[cfg(feature = "tracing")]
+    if let Some(filter) = &cli.log {
+        init_tracing(filter);
+    }
+
+    let result = match cli.command {
+        Command::Run {
+            day,
+            part,
+            input,
+            #[cfg(feature = "alloc")]
+            alloc,
+            examples,
+            json,
+            timeout,
+            max_steps,
+            implementation,
+        } => run(RunArgs {
+            day,
+            part,
+            input,
+            examples,
+            json,
+            options: RunOptions {
+                #[cfg(feature = "alloc")]
+                profile_alloc: alloc,
+                timeout: timeout_from_secs(timeout),
+                max_steps,
+                implementation,
+                ..RunOptions::default()
+            },
+        }),
+        Command::Compare {
+            day,
+            part,
+            input,
+            runs,
+            timeout,
+        } => compare_day(day, part, input, runs, timeout_from_secs(timeout)),
+        Command::Shrink {
+            day,
+            part,
+            input,
+            timeout,
+        } => shrink_input(day, part, &input, timeout_from_secs(timeout)).map(|()| true),
+        Command::Report {
+            html,
+            timeout: seconds,
+        } => report(&html, timeout_from_secs(seconds)).map(|()| true),
+        Command::Serve {
+            addr,
+            max_body,
+            timeout,
+            max_solvers,
+        } => serve_api(&addr, max_body, timeout_from_secs(timeout), max_solvers).map(|()| true),
+        Command::Fetch { day } => fetch(day).map(|()| true),
+        Command::Extract {
+            day,
+            page,
+            overwrite,
+        } => extract_examples(day, &page, overwrite).map(|()| true),
+        Command::Record {
+            day,
+            part,
+            answer,
+            verdict,
+        } => record(day, part, &answer, verdict).map(|()| true),
+        Command::Identify { file, run, timeout } => {
+            identify_file(&file, run, timeout_from_secs(timeout))
+        }
+        Command::Tui { day, input } => step_through(day, input).map(|()| true),
+        Command::Watch { day, plugin } => watch(workspace_root(), day, plugin).map(|()| true),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
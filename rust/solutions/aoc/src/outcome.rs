@@ -0,0 +1,194 @@
+use std::{fmt, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    alloc::AllocStats, fixtures, ledger::read_ledger, read_input, solve, Day, Error, RunOptions,
+};
+
+/// Source name used for the day's real puzzle input
+pub const INPUT: &str = "input";
+
+/// The result of running one part against one input, which is what `aoc run
+/// --json` prints and `aoc watch` compares between builds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Outcome {
+    pub day: u8,
+    pub part: u8,
+    /// [`INPUT`], or the name of a fixture
+    pub source: String,
+    pub answer: Option<String>,
+    pub error: Option<String>,
+    pub expected: Option<String>,
+    pub elapsed_ms: f64,
+    pub allocations: Option<AllocStats>,
+    /// Set when the answer clashes with the submissions ledger
+    #[serde(default)]
+    pub warning: Option<String>,
+}
+
+impl Outcome {
+    fn new(
+        day: &Day,
+        part: u8,
+        source: &str,
+        input: &str,
+        expected: Option<&str>,
+        options: &RunOptions,
+    ) -> Result<Self, Error> {
+        let mut outcome = Outcome {
+            day: day.day,
+            part,
+            source: source.to_string(),
+            answer: None,
+            error: None,
+            expected: expected.map(str::to_string),
+            elapsed_ms: 0.0,
+            allocations: None,
+            warning: None,
+        };
+
+        match solve(day, part, input, options) {
+            Ok(answer) => {
+                outcome.answer = Some(answer.answer);
+                outcome.elapsed_ms = answer.elapsed.as_secs_f64() * 1000.0;
+                outcome.allocations = answer.allocations;
+            }
+            // A broken solver is something to report, not a reason to stop
+            Err(err) if err.is_solver_failure() => outcome.error = Some(err.to_string()),
+            Err(err) => return Err(err),
+        }
+
+        Ok(outcome)
+    }
+
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+            && (self.expected.is_none() || self.expected.as_deref() == self.answer.as_deref())
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {:02} part {}", self.day, self.part)?;
+        if self.source != INPUT {
+            write!(f, " [{}]", self.source)?;
+        }
+
+        match (&self.answer, &self.error) {
+            (_, Some(error)) => return write!(f, ": {error}"),
+            (Some(answer), None) => write!(f, ": {answer}")?,
+            (None, None) => write!(f, ": no answer")?,
+        }
+
+        match self.expected.as_deref() {
+            Some(expected) if self.answer.as_deref() == Some(expected) => write!(f, " ok")?,
+            Some(expected) => write!(f, ", expected {expected}")?,
+            None => {}
+        }
+
+        write!(f, " ({:.2}ms)", self.elapsed_ms)?;
+        if let Some(stats) = &self.allocations {
+            write!(f, " [{stats}]")?;
+        }
+        if let Some(warning) = &self.warning {
+            write!(f, "\n  WARNING: {warning}, don't submit it")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs the requested parts of a day against its examples (if asked for) and
+/// then its puzzle input, when there is one
+pub fn run_day(
+    root: &Path,
+    day: &Day,
+    parts: &[u8],
+    input: Option<&Path>,
+    examples: bool,
+    options: &RunOptions,
+) -> Result<Vec<Outcome>, Error> {
+    let mut outcomes = Vec::new();
+
+    if examples {
+        for fixture in fixtures::load_day(root, day.day)? {
+            for &part in parts {
+                // A fixture only counts for the parts it has an answer for
+                if let Some(expected) = fixture.expected.part(part) {
+                    outcomes.push(Outcome::new(
+                        day,
+                        part,
+                        &fixture.name,
+                        &fixture.input,
+                        Some(expected),
+                        options,
+                    )?);
+                }
+            }
+        }
+    }
+
+    if let Some(path) = input {
+        let puzzle_input = read_input(path)?;
+        let ledger = read_ledger(root)?;
+
+        for &part in parts {
+            let mut outcome = Outcome::new(day, part, INPUT, &puzzle_input, None, options)?;
+            outcome.warning = outcome
+                .answer
+                .as_deref()
+                .and_then(|answer| ledger.check(day.day, part, answer))
+                .map(|warning| format!("{} is {warning}", outcome.answer.as_deref().unwrap()));
+
+            outcomes.push(outcome);
+        }
+    }
+
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{find_day, workspace_root};
+
+    #[test]
+    fn runs_examples_for_the_requested_parts() {
+        let day = find_day(3).unwrap();
+        let outcomes = run_day(
+            workspace_root(),
+            day,
+            &[1],
+            None,
+            true,
+            &RunOptions::default(),
+        )
+        .unwrap();
+
+        let sources: Vec<&str> = outcomes.iter().map(|o| o.source.as_str()).collect();
+        assert_eq!(sources, ["example", "reddit"]);
+        assert!(outcomes.iter().all(Outcome::passed));
+    }
+
+    #[test]
+    fn shows_failures_against_the_expected_answer() {
+        let outcome = Outcome {
+            day: 6,
+            part: 2,
+            source: "example".to_string(),
+            answer: Some("1".to_string()),
+            error: None,
+            expected: Some("71503".to_string()),
+            elapsed_ms: 0.5,
+            allocations: None,
+            warning: None,
+        };
+
+        assert!(!outcome.passed());
+        assert_eq!(
+            outcome.to_string(),
+            "day 06 part 2 [example]: 1, expected 71503 (0.50ms)"
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! Days loaded from their own `cdylib` builds rather than the copies linked
+//! into the runner, so `aoc watch --plugin` can rebuild one day and swap it in
+//! without rebuilding or restarting anything else. `common::plugin` has the
+//! other side of the interface.
+//!
+//! A plugin has its own copy of `common`, so its solvers don't see the
+//! runner's budget: timeouts still hold, but `--max-steps` doesn't.
+
+use std::{
+    collections::BTreeMap,
+    env::{
+        self,
+        consts::{DLL_PREFIX, DLL_SUFFIX},
+    },
+    fs, panic,
+    path::{Path, PathBuf},
+    process::{self, Command},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+    time::SystemTime,
+};
+
+use common::plugin::{Descriptor, ABI_VERSION, SYMBOL};
+use libloading::Library;
+
+use crate::{Day, Error, Solver};
+
+/// A loaded day library
+pub struct Plugin {
+    descriptor: *const Descriptor,
+    library: Library,
+    copy: PathBuf,
+}
+
+// The descriptor is immutable, and stays valid for as long as the plugin keeps
+// its library loaded
+unsafe impl Send for Plugin {}
+unsafe impl Sync for Plugin {}
+
+// Numbers each load's copy of a library
+static LOADS: AtomicUsize = AtomicUsize::new(0);
+
+fn load_error(path: &Path, message: impl std::fmt::Display) -> Error {
+    Error::Plugin(format!("{}: {message}", path.display()))
+}
+
+impl Plugin {
+    /// Loads a copy of the library at `path`, so the next build is free to
+    /// overwrite it, and so the copy's fresh name can't be mistaken for a
+    /// library that's already loaded
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| load_error(path, "not a file"))?;
+        // Beside the original, so `cargo clean` sweeps up any left by a runner
+        // that was killed
+        let copy = path.with_file_name(format!(
+            "loaded-{}-{}-{}",
+            process::id(),
+            LOADS.fetch_add(1, Ordering::Relaxed),
+            file_name.to_string_lossy()
+        ));
+        fs::copy(path, &copy).map_err(|err| load_error(path, err))?;
+
+        let library = match unsafe { Library::new(&copy) } {
+            Ok(library) => library,
+            Err(err) => {
+                let _ = fs::remove_file(&copy);
+                return Err(load_error(path, err));
+            }
+        };
+
+        // From here on, dropping the plugin cleans up after it
+        let mut plugin = Plugin {
+            descriptor: std::ptr::null(),
+            library,
+            copy,
+        };
+        let descriptor =
+            unsafe { plugin.library.get::<*const Descriptor>(SYMBOL) }.map(|symbol| *symbol);
+        plugin.descriptor = descriptor
+            .map_err(|err| load_error(path, format!("{err} (was it built with `plugin`?)")))?;
+
+        // Every version keeps `abi_version` first, so this much can always be read
+        let abi_version = plugin.descriptor().abi_version;
+        if abi_version != ABI_VERSION {
+            return Err(load_error(
+                path,
+                format!("built for plugin ABI {abi_version}, but the runner uses {ABI_VERSION}"),
+            ));
+        }
+
+        Ok(plugin)
+    }
+
+    fn descriptor(&self) -> &Descriptor {
+        unsafe { &*self.descriptor }
+    }
+
+    pub fn day(&self) -> u8 {
+        self.descriptor().day
+    }
+
+    /// Runs one part. A panic in the library is caught there and raised again
+    /// here, for the runner to report like any other.
+    pub fn solve(&self, part: u8, input: &str) -> common::Result<String> {
+        let descriptor = self.descriptor();
+        let solver = descriptor.parts[part as usize - 1];
+
+        let answer = unsafe {
+            let raw = solver(input.as_ptr(), input.len());
+            let answer = raw.read();
+            (descriptor.free)(raw);
+            answer
+        };
+
+        answer.unwrap_or_else(|message| panic::resume_unwind(Box::new(message)))
+    }
+}
+
+impl Drop for Plugin {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.copy);
+    }
+}
+
+// The plugin each day runs on. Solvers still running on a replaced plugin
+// hold on to it until they finish.
+static LOADED: RwLock<BTreeMap<u8, Arc<Plugin>>> = RwLock::new(BTreeMap::new());
+
+fn loaded<const DAY: u8, const PART: u8>(input: &str) -> common::Result<String> {
+    let plugin = LOADED
+        .read()
+        .expect("plugin table shouldn't be poisoned")
+        .get(&DAY)
+        .cloned()
+        .expect("day should have a plugin installed");
+
+    plugin.solve(PART, input)
+}
+
+// `Day` holds plain function pointers, so every day of the calendar gets a
+// pair of its own that look up its plugin
+macro_rules! trampolines {
+    ($($day:literal)*) => {
+        [$([loaded::<$day, 1> as Solver, loaded::<$day, 2>]),*]
+    };
+}
+
+static TRAMPOLINES: [[Solver; 2]; 25] =
+    trampolines!(1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25);
+
+/// Makes `plugin` the one its day runs on, returning the day to solve with
+pub fn install(plugin: Plugin) -> Result<Day, Error> {
+    let day = plugin.day();
+    let parts = usize::from(day)
+        .checked_sub(1)
+        .and_then(|index| TRAMPOLINES.get(index))
+        .ok_or_else(|| Error::Plugin(format!("day {day} isn't in the calendar")))?;
+
+    LOADED
+        .write()
+        .expect("plugin table shouldn't be poisoned")
+        .insert(day, Arc::new(plugin));
+
+    Ok(Day {
+        day,
+        parts: *parts,
+        alternatives: &[],
+    })
+}
+
+// Kept apart from the runner's own target dir, where the `plugin` feature
+// would otherwise be switched on for the runner's build too
+fn target_dir(root: &Path) -> PathBuf {
+    root.join("target").join("plugins")
+}
+
+/// Where [`build`] leaves a day's library
+pub fn library_path(root: &Path, day: u8) -> PathBuf {
+    target_dir(root)
+        .join("debug")
+        .join(format!("{DLL_PREFIX}day_{day:02}{DLL_SUFFIX}"))
+}
+
+/// Builds just one day, as a `cdylib` with its `plugin` feature. On failure,
+/// returns what cargo said.
+pub fn build(root: &Path, day: u8) -> Result<(), String> {
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = Command::new(cargo)
+        .current_dir(root)
+        .args(["rustc", "-q", "--lib", "--crate-type", "cdylib"])
+        .args(["--features", "plugin", "-p"])
+        .arg(format!("day-{day:02}"))
+        .arg("--target-dir")
+        .arg(target_dir(root))
+        .output()
+        .map_err(|err| format!("could not start cargo: {err}"))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+
+    Ok(())
+}
+
+/// Keeps a day's plugin in step with its library file
+pub struct Reloader {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    day: Option<Day>,
+}
+
+impl Reloader {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            modified: None,
+            day: None,
+        }
+    }
+
+    /// Loads the library again if it's changed since the last load, returning
+    /// whether it did
+    pub fn reload(&mut self) -> Result<bool, Error> {
+        let modified = fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|err| load_error(&self.path, err))?;
+        if self.day.is_some() && self.modified == Some(modified) {
+            return Ok(false);
+        }
+
+        self.day = Some(install(Plugin::load(&self.path)?)?);
+        self.modified = Some(modified);
+        Ok(true)
+    }
+
+    /// The day as of the last load
+    pub fn day(&self) -> Option<&Day> {
+        self.day.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn libraries_go_in_their_own_target_dir() {
+        let path = library_path(Path::new("/solutions"), 4);
+
+        assert!(path.starts_with("/solutions/target/plugins/debug"));
+        assert!(path.to_string_lossy().contains("day_04"));
+    }
+
+    #[test]
+    fn files_that_arent_libraries_are_turned_away() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("libday_04.so");
+        fs::write(&path, "not a library").unwrap();
+
+        assert!(matches!(Plugin::load(&path), Err(Error::Plugin(_))));
+        assert!(matches!(
+            Reloader::new(dir.path().join("missing.so")).reload(),
+            Err(Error::Plugin(_))
+        ));
+    }
+}
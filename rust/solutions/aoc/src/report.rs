@@ -0,0 +1,218 @@
+use std::{
+    fmt::Write,
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    days, fixtures, input_path,
+    outcome::{run_day, Outcome, INPUT},
+    read_input,
+    visualise::visualise,
+    Error, RunOptions,
+};
+
+/// Everything the report shows for one day
+#[derive(Debug, Clone)]
+pub struct DayReport {
+    pub day: u8,
+    pub outcomes: Vec<Outcome>,
+    pub has_input: bool,
+    /// SVG picture of the puzzle, saved next to the report
+    pub picture: Option<String>,
+}
+
+pub fn picture_file(day: u8) -> String {
+    format!("day-{day:02}.svg")
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Pictures are drawn from the real input when we have it, and otherwise from
+// the first example
+fn picture(root: &Path, day: u8) -> Result<Option<String>, Error> {
+    let path = input_path(root, day);
+    let input = if path.exists() {
+        read_input(&path)?
+    } else {
+        match fixtures::load_day(root, day)?.into_iter().next() {
+            Some(fixture) => fixture.input,
+            None => return Ok(None),
+        }
+    };
+
+    // A picture is nice to have, so input it can't cope with just means no picture
+    Ok(panic::catch_unwind(AssertUnwindSafe(|| visualise(day, &input))).unwrap_or(None))
+}
+
+/// Runs every day's examples and input, and draws the days that have a picture
+pub fn build(root: &Path, options: &RunOptions) -> Result<Vec<DayReport>, Error> {
+    days()
+        .iter()
+        .map(|day| {
+            let path = input_path(root, day.day);
+            let has_input = path.exists();
+            let outcomes = run_day(
+                root,
+                day,
+                &[1, 2],
+                has_input.then_some(path.as_path()),
+                true,
+                options,
+            )?;
+
+            Ok(DayReport {
+                day: day.day,
+                outcomes,
+                has_input,
+                picture: picture(root, day.day)?,
+            })
+        })
+        .collect()
+}
+
+fn status(outcome: &Outcome) -> (&'static str, String) {
+    match (&outcome.error, &outcome.expected) {
+        (Some(error), _) => ("fail", escape(error)),
+        (None, Some(expected)) if outcome.passed() => {
+            ("pass", format!("expected {}", escape(expected)))
+        }
+        (None, Some(expected)) => ("fail", format!("expected {}", escape(expected))),
+        (None, None) => match &outcome.warning {
+            Some(warning) => ("warn", escape(warning)),
+            None => ("", String::new()),
+        },
+    }
+}
+
+const STYLE: &str = "
+body { background: #0f0f23; color: #cccccc; font-family: 'Source Code Pro', monospace; margin: 2em; }
+h1 { color: #00cc00; }
+h2 { color: #ffffff; margin-top: 2em; }
+a { color: #009900; }
+table { border-collapse: collapse; }
+th, td { padding: 0.2em 1em; text-align: left; }
+th { color: #ffffff; }
+td.answer { color: #ffff66; }
+.pass { color: #00cc00; }
+.fail { color: #ff4040; }
+.warn { color: #f2a03d; }
+.muted { color: #666666; }
+";
+
+/// Renders the report as one HTML page, with no scripts or external styles
+pub fn render_html(reports: &[DayReport]) -> String {
+    let outcomes = reports.iter().flat_map(|report| &report.outcomes);
+    let examples: Vec<&Outcome> = outcomes.clone().filter(|o| o.source != INPUT).collect();
+    let passing = examples.iter().filter(|o| o.passed()).count();
+    let solved = outcomes
+        .filter(|o| o.source == INPUT && o.answer.is_some())
+        .count();
+
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Advent of Code 2023</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>Advent of Code 2023</h1>\n\
+         <p>{} days, {solved} parts answered, {passing}/{} examples passing</p>\n",
+        reports.len(),
+        examples.len()
+    );
+
+    for report in reports {
+        let _ = writeln!(html, "<h2 id=\"day-{0:02}\">Day {0}</h2>", report.day);
+        if !report.has_input {
+            let _ = writeln!(
+                html,
+                "<p class=\"muted\">No puzzle input, examples only</p>"
+            );
+        }
+
+        html.push_str("<table>\n<tr><th>Part</th><th>Source</th><th>Answer</th><th>Time</th><th>Status</th></tr>\n");
+        for outcome in &report.outcomes {
+            let (class, detail) = status(outcome);
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td class=\"answer\">{}</td><td>{:.2}ms</td><td class=\"{class}\">{}</td></tr>",
+                outcome.part,
+                escape(&outcome.source),
+                escape(outcome.answer.as_deref().unwrap_or("")),
+                outcome.elapsed_ms,
+                match class {
+                    "pass" => format!("ok, {detail}"),
+                    _ => detail,
+                }
+            );
+        }
+        html.push_str("</table>\n");
+
+        if report.picture.is_some() {
+            let _ = writeln!(
+                html,
+                "<p><a href=\"{0}\"><img src=\"{0}\" alt=\"Day {1} picture\" style=\"max-width: 40em\"></a></p>",
+                picture_file(report.day),
+                report.day
+            );
+        }
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn report_error(path: &Path, source: std::io::Error) -> Error {
+    Error::Output {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+/// Writes `index.html` and the day pictures into `out`, returning the page's path
+pub fn write_report(root: &Path, out: &Path, options: &RunOptions) -> Result<PathBuf, Error> {
+    fs::create_dir_all(out).map_err(|source| report_error(out, source))?;
+
+    let reports = build(root, options)?;
+    for report in &reports {
+        if let Some(svg) = &report.picture {
+            let path = out.join(picture_file(report.day));
+            fs::write(&path, svg).map_err(|source| report_error(&path, source))?;
+        }
+    }
+
+    let index = out.join("index.html");
+    fs::write(&index, render_html(&reports)).map_err(|source| report_error(&index, source))?;
+
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace_root;
+
+    #[test]
+    fn report_links_pictures_and_shows_examples() {
+        let out = tempfile::tempdir().unwrap();
+        let index = write_report(workspace_root(), out.path(), &RunOptions::default()).unwrap();
+        let html = fs::read_to_string(index).unwrap();
+
+        assert!(html.contains("<h2 id=\"day-06\">Day 6</h2>"));
+        assert!(html.contains("<td class=\"answer\">288</td>"));
+        assert!(html.contains("<img src=\"day-10.svg\""));
+        assert!(out.path().join("day-10.svg").exists());
+        assert!(out.path().join("day-08.svg").exists());
+        assert!(!html.contains("class=\"fail\""));
+    }
+
+    #[test]
+    fn escapes_answers() {
+        assert_eq!(escape("<a & \"b\">"), "&lt;a &amp; &quot;b&quot;&gt;");
+    }
+}
@@ -0,0 +1,389 @@
+//! A small HTTP API over the solvers: `POST /day/{n}/part/{p}` with the
+//! puzzle input as the body answers with `{"answer", "elapsed_ms"}`, or with
+//! `{"error", "message"}` and a matching status when it can't
+//!
+//! Only `max_solvers` requests are solved at once, and the rest get a 503.
+//! A timeout cancels the solver, which gets a second's grace to stop before
+//! the 504 goes out. Only a solver that calls [`common::budget::tick`] notices,
+//! so one that doesn't keeps its place until it finishes.
+
+use std::{
+    io::Read,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::{days, solve_holding, Day, Error, RunOptions};
+
+#[derive(Clone)]
+pub struct ServeOptions {
+    /// Largest input accepted, in bytes. Real inputs are a few tens of KB
+    pub max_body: u64,
+    /// Wall-clock limit for each request's solver
+    pub timeout: Option<Duration>,
+    /// Requests solved at once, counting solvers abandoned after timing out
+    pub max_solvers: usize,
+    pub days: &'static [Day],
+}
+
+impl Default for ServeOptions {
+    fn default() -> Self {
+        Self {
+            max_body: 1024 * 1024,
+            timeout: Some(Duration::from_secs(10)),
+            max_solvers: 4,
+            days: days(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    pub status: u16,
+    /// Always a JSON object
+    pub body: String,
+}
+
+#[derive(Serialize)]
+struct Solved<'a> {
+    answer: &'a str,
+    elapsed_ms: f64,
+}
+
+#[derive(Serialize)]
+struct Failure<'a> {
+    error: &'a str,
+    message: String,
+}
+
+fn failure(status: u16, error: &str, message: impl ToString) -> Reply {
+    let body = Failure {
+        error,
+        message: message.to_string(),
+    };
+
+    Reply {
+        status,
+        body: serde_json::to_string(&body).expect("failures should serialize"),
+    }
+}
+
+// /day/6/part/1 -> (6, 1), ignoring any query string
+fn route(url: &str) -> Option<(u8, u8)> {
+    let path = url.split('?').next()?;
+    match path.split('/').collect::<Vec<_>>()[..] {
+        ["", "day", day, "part", part] => Some((day.parse().ok()?, part.parse().ok()?)),
+        _ => None,
+    }
+}
+
+/// A request's place among the `max_solvers`, given up when its solver
+/// finishes
+struct Slot(Arc<AtomicUsize>);
+
+impl Slot {
+    fn take(taken: &Arc<AtomicUsize>, max: usize) -> Option<Self> {
+        taken
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < max).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Self(Arc::clone(taken)))
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+// The day a request is for, if it has a solver for the part
+fn find_day(day: u8, part: u8, options: &ServeOptions) -> Result<&Day, Reply> {
+    let Some(solutions) = options.days.iter().find(|d| d.day == day) else {
+        return Err(failure(404, "not-found", Error::UnknownDay(day)));
+    };
+
+    match solutions.solver(part, None) {
+        Ok(_) => Ok(solutions),
+        Err(err) => Err(failure(404, "not-found", err)),
+    }
+}
+
+/// Runs one part on a request's input, with the server's limits
+pub fn solve_request(day: u8, part: u8, input: &str, options: &ServeOptions) -> Reply {
+    match find_day(day, part, options) {
+        Ok(solutions) => solve_request_holding(solutions, part, input, options, ()),
+        Err(reply) => reply,
+    }
+}
+
+// Keeps `slot` until the solver finishes, however long after the reply
+fn solve_request_holding(
+    solutions: &Day,
+    part: u8,
+    input: &str,
+    options: &ServeOptions,
+    slot: impl Send + 'static,
+) -> Reply {
+    // Bad inputs are answered with a 422, not logged to the server's stderr
+    let run = RunOptions {
+        timeout: options.timeout,
+        quiet_panics: true,
+        ..RunOptions::default()
+    };
+
+    match solve_holding(solutions, part, input, &run, slot) {
+        Ok(answer) => {
+            let body = Solved {
+                answer: &answer.answer,
+                elapsed_ms: answer.elapsed.as_secs_f64() * 1000.0,
+            };
+            Reply {
+                status: 200,
+                body: serde_json::to_string(&body).expect("answers should serialize"),
+            }
+        }
+        Err(err @ (Error::UnknownPart(_) | Error::UnknownImpl { .. })) => {
+            failure(404, "not-found", err)
+        }
+        // The days mostly reject input they can't parse by panicking
+        Err(err @ (Error::Solver { .. } | Error::Panic { .. })) => {
+            failure(422, "invalid-input", err)
+        }
+        Err(err @ (Error::TimedOut { .. } | Error::OutOfSteps { .. })) => {
+            failure(504, "timed-out", err)
+        }
+        Err(err) => failure(500, "internal", err),
+    }
+}
+
+// Reads at most `max_body` bytes, without trusting Content-Length to say
+// how many are coming
+fn read_body(request: &mut Request, max_body: u64) -> Result<String, Reply> {
+    let too_large = || failure(413, "too-large", format!("input is over {max_body} bytes"));
+    if request
+        .body_length()
+        .is_some_and(|length| length as u64 > max_body)
+    {
+        return Err(too_large());
+    }
+
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(max_body + 1)
+        .read_to_end(&mut body)
+        .map_err(|err| failure(400, "bad-body", err))?;
+    if body.len() as u64 > max_body {
+        return Err(too_large());
+    }
+
+    String::from_utf8(body).map_err(|_| failure(400, "bad-body", "input should be UTF-8"))
+}
+
+// Only a request that's going to be solved takes one of the places
+fn reply(request: &mut Request, options: &ServeOptions, taken: &Arc<AtomicUsize>) -> Reply {
+    let Some((day, part)) = route(request.url()) else {
+        return failure(404, "not-found", format!("no route for {}", request.url()));
+    };
+    if *request.method() != Method::Post {
+        return failure(405, "method-not-allowed", "POST the input to this URL");
+    }
+
+    let input = match read_body(request, options.max_body) {
+        Ok(input) => input,
+        Err(reply) => return reply,
+    };
+    let solutions = match find_day(day, part, options) {
+        Ok(solutions) => solutions,
+        Err(reply) => return reply,
+    };
+
+    let Some(slot) = Slot::take(taken, options.max_solvers) else {
+        let message = format!("already solving {} inputs", options.max_solvers);
+        return failure(503, "busy", message);
+    };
+
+    solve_request_holding(solutions, part, &input, options, slot)
+}
+
+fn respond(request: Request, reply: Reply) {
+    let response = Response::from_string(reply.body)
+        .with_status_code(reply.status)
+        .with_header(
+            Header::from_bytes("Content-Type", "application/json").expect("header should be valid"),
+        );
+
+    // The client may have hung up, which is its business
+    let _ = request.respond(response);
+}
+
+pub fn bind(addr: &str) -> Result<Server, Error> {
+    Server::http(addr).map_err(|err| Error::Serve(format!("could not listen on {addr}: {err}")))
+}
+
+/// Answers requests until the server is dropped, each on its own thread so a
+/// slow day doesn't hold up the others, and turning away any over
+/// `max_solvers` with a 503
+pub fn serve(server: Server, options: ServeOptions) {
+    let options = Arc::new(options);
+    let taken = Arc::new(AtomicUsize::new(0));
+
+    for request in server.incoming_requests() {
+        let options = Arc::clone(&options);
+        let taken = Arc::clone(&taken);
+        thread::spawn(move || {
+            let mut request = request;
+            let reply = reply(&mut request, &options, &taken);
+            respond(request, reply);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::budget;
+    use serde_json::Value;
+
+    const EXAMPLE: &str = "Time:      7  15   30
+Distance:  9  40  200";
+
+    // Serves on a free local port, returning its base URL
+    fn start(options: ServeOptions) -> String {
+        let server = bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", server.server_addr());
+        thread::spawn(move || serve(server, options));
+        base_url
+    }
+
+    fn agent() -> ureq::Agent {
+        ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .build()
+            .into()
+    }
+
+    fn post(url: &str, body: &str) -> (u16, Value) {
+        let mut response = agent().post(url).send(body).unwrap();
+        let status = response.status().as_u16();
+        let body = response.body_mut().read_to_string().unwrap();
+        (status, serde_json::from_str(&body).unwrap())
+    }
+
+    #[test]
+    fn answers_with_json() {
+        let base_url = start(ServeOptions::default());
+
+        let (status, body) = post(&format!("{base_url}/day/6/part/1"), EXAMPLE);
+        assert_eq!(status, 200);
+        assert_eq!(body["answer"], "288");
+        assert!(body["elapsed_ms"].as_f64().unwrap() >= 0.0);
+
+        let (status, body) = post(&format!("{base_url}/day/6/part/2"), EXAMPLE);
+        assert_eq!((status, &body["answer"]), (200, &Value::from("71503")));
+    }
+
+    #[test]
+    fn bad_requests_get_structured_errors() {
+        let base_url = start(ServeOptions {
+            max_body: 64,
+            ..ServeOptions::default()
+        });
+
+        let (status, body) = post(&format!("{base_url}/day/6/part/1"), "Time: 7");
+        assert_eq!(
+            (status, &body["error"]),
+            (422, &Value::from("invalid-input"))
+        );
+        assert!(body["message"].as_str().unwrap().contains("panicked"));
+
+        for path in ["/day/26/part/1", "/day/6/part/3", "/day/six/part/1", "/"] {
+            let (status, body) = post(&format!("{base_url}{path}"), EXAMPLE);
+            assert_eq!((status, &body["error"]), (404, &Value::from("not-found")));
+        }
+
+        let (status, body) = post(&format!("{base_url}/day/6/part/1"), &"7 ".repeat(40));
+        assert_eq!((status, &body["error"]), (413, &Value::from("too-large")));
+
+        let response = agent()
+            .get(format!("{base_url}/day/6/part/1"))
+            .call()
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 405);
+    }
+
+    const SPINNING: &[Day] = &[Day {
+        day: 99,
+        parts: [
+            |_| loop {
+                budget::tick();
+            },
+            |_| Ok("done".to_string()),
+        ],
+        alternatives: &[],
+    }];
+
+    #[test]
+    fn slow_requests_time_out() {
+        let base_url = start(ServeOptions {
+            timeout: Some(Duration::from_millis(50)),
+            days: SPINNING,
+            ..ServeOptions::default()
+        });
+
+        let (status, body) = post(&format!("{base_url}/day/99/part/1"), "");
+        assert_eq!((status, &body["error"]), (504, &Value::from("timed-out")));
+
+        let (status, body) = post(&format!("{base_url}/day/99/part/2"), "");
+        assert_eq!((status, &body["answer"]), (200, &Value::from("done")));
+    }
+
+    const SLEEPING: &[Day] = &[Day {
+        day: 99,
+        parts: [
+            |_| {
+                thread::sleep(Duration::from_secs(2));
+                Ok("late".to_string())
+            },
+            |_| Ok("done".to_string()),
+        ],
+        alternatives: &[],
+    }];
+
+    #[test]
+    fn abandoned_solvers_keep_their_place() {
+        let base_url = start(ServeOptions {
+            timeout: Some(Duration::from_millis(50)),
+            max_solvers: 1,
+            days: SLEEPING,
+            ..ServeOptions::default()
+        });
+
+        // It never ticks, so it's still sleeping after the 504
+        let (status, _) = post(&format!("{base_url}/day/99/part/1"), "");
+        assert_eq!(status, 504);
+
+        let (status, body) = post(&format!("{base_url}/day/99/part/2"), "");
+        assert_eq!((status, &body["error"]), (503, &Value::from("busy")));
+
+        // Requests that won't be solved don't need a place
+        let (status, _) = post(&format!("{base_url}/day/98/part/1"), "");
+        assert_eq!(status, 404);
+    }
+
+    #[test]
+    fn routes_ignore_query_strings() {
+        assert_eq!(route("/day/10/part/2?pretty"), Some((10, 2)));
+        assert_eq!(route("/day/10/part/2/extra"), None);
+    }
+}
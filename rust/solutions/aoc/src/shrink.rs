@@ -0,0 +1,290 @@
+//! Delta debugging for inputs that crash a solver or make implementations
+//! disagree: lines are removed in ever smaller chunks for as long as the
+//! failure still happens, leaving a small input to keep as a fixture
+
+use std::{fmt, fs, path::Path};
+
+use crate::{
+    fixtures::{day_fixtures_dir, read_manifest, set_expected, Expected},
+    solve, Day, Error, RunOptions,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    /// An implementation panicked, returned an error or ran out of time.
+    /// Holds what it said, without anything that moves as the input shrinks.
+    Crash { name: &'static str, reason: String },
+    /// Every implementation finished, but not with the same answer
+    Disagreement,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Crash { name, reason } => write!(f, "{name} {reason}"),
+            Failure::Disagreement => write!(f, "implementations disagree"),
+        }
+    }
+}
+
+// Parse errors say where they happened, which changes as lines go, so only
+// the kind of failure is compared
+fn reason(err: &Error) -> String {
+    match err {
+        Error::Panic { message, .. } => format!("panicked: {message}"),
+        Error::Solver {
+            source: common::Error::Parse { .. },
+            ..
+        } => "failed to parse".to_string(),
+        Error::Solver { source, .. } => format!("failed: {source}"),
+        _ => "ran out of time".to_string(),
+    }
+}
+
+/// How the part fails on this input, if it does
+pub fn failure(
+    day: &Day,
+    part: u8,
+    input: &str,
+    options: &RunOptions,
+) -> Result<Option<Failure>, Error> {
+    let mut answers = Vec::new();
+    for (name, _) in day.implementations(part)? {
+        // Many candidates crash, and their reasons are kept in the failure
+        let options = RunOptions {
+            implementation: Some(name.to_string()),
+            quiet_panics: true,
+            ..options.clone()
+        };
+
+        match solve(day, part, input, &options) {
+            Ok(answer) => answers.push(answer.answer),
+            Err(err) if err.is_solver_failure() => {
+                return Ok(Some(Failure::Crash {
+                    name,
+                    reason: reason(&err),
+                }))
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    let disagree = answers.windows(2).any(|pair| pair[0] != pair[1]);
+    Ok(disagree.then_some(Failure::Disagreement))
+}
+
+// Lines that hold the input's structure together, which are never removed:
+// blank lines between blocks, day 05's seeds and map headings, and day 08's
+// instructions
+fn pinned(day: u8, index: usize, line: &str) -> bool {
+    line.trim().is_empty()
+        || match day {
+            5 => line.starts_with("seeds:") || line.ends_with("map:"),
+            // Times and distances only make sense together
+            6 => true,
+            8 => index == 0,
+            _ => false,
+        }
+}
+
+/// The smallest subset of `units` that `fails` still holds for, found by
+/// removing chunks of them, halving the chunk size whenever nothing can go.
+/// Keeps the units in order.
+pub fn ddmin<T: Clone>(units: Vec<T>, mut fails: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut units = units;
+    let mut chunks = 2;
+
+    while units.len() >= 2 {
+        let size = units.len().div_ceil(chunks);
+        let reduced = (0..units.len()).step_by(size).find_map(|start| {
+            let rest: Vec<T> = units[..start]
+                .iter()
+                .chain(&units[(start + size).min(units.len())..])
+                .cloned()
+                .collect();
+            fails(&rest).then_some(rest)
+        });
+
+        match reduced {
+            Some(rest) => {
+                units = rest;
+                chunks = (chunks - 1).max(2);
+            }
+            None if chunks >= units.len() => break,
+            None => chunks = (chunks * 2).min(units.len()),
+        }
+    }
+
+    if units.len() == 1 && fails(&[]) {
+        units.clear();
+    }
+
+    units
+}
+
+#[derive(Debug, Clone)]
+pub struct Shrunk {
+    pub input: String,
+    pub failure: Failure,
+    pub lines_before: usize,
+    pub lines_after: usize,
+    /// How many candidate inputs were run
+    pub runs: usize,
+}
+
+/// Shrinks an input that fails, keeping only inputs that fail the same way
+pub fn shrink(day: &Day, part: u8, input: &str, options: &RunOptions) -> Result<Shrunk, Error> {
+    let original = failure(day, part, input, options)?.ok_or_else(|| {
+        Error::Shrink(format!(
+            "day {:02} part {part} doesn't fail on this input, so there's nothing to shrink",
+            day.day
+        ))
+    })?;
+
+    let lines: Vec<&str> = input.lines().collect();
+    let trailing = if input.ends_with('\n') { "\n" } else { "" };
+    let render = |kept: &[usize]| {
+        let text: Vec<&str> = lines
+            .iter()
+            .enumerate()
+            .filter(|&(i, line)| pinned(day.day, i, line) || kept.binary_search(&i).is_ok())
+            .map(|(_, line)| *line)
+            .collect();
+        text.join("\n") + trailing
+    };
+
+    let removable: Vec<usize> = (0..lines.len())
+        .filter(|&i| !pinned(day.day, i, lines[i]))
+        .collect();
+
+    let mut runs = 0;
+    let mut error = None;
+    let kept = ddmin(removable, |candidate| {
+        runs += 1;
+        match failure(day, part, &render(candidate), options) {
+            Ok(found) => found.as_ref() == Some(&original),
+            Err(err) => {
+                error.get_or_insert(err);
+                false
+            }
+        }
+    });
+    if let Some(err) = error {
+        return Err(err);
+    }
+
+    let input = render(&kept);
+    Ok(Shrunk {
+        lines_before: lines.len(),
+        lines_after: input.lines().count(),
+        input,
+        failure: original,
+        runs,
+    })
+}
+
+/// Saves a shrunk input as a new fixture, named `shrunk` or `shrunk-N` so
+/// nothing is overwritten. Its answers are left for whoever works out what
+/// they should be, and the fixtures test fails until they're filled in.
+pub fn write_fixture(root: &Path, day: u8, input: &str) -> Result<String, Error> {
+    let dir = day_fixtures_dir(root, day);
+    let manifest = read_manifest(&dir)?;
+
+    let name = (1..)
+        .map(|n| match n {
+            1 => "shrunk".to_string(),
+            n => format!("shrunk-{n}"),
+        })
+        .find(|name| !manifest.contains_key(name) && !dir.join(format!("{name}.txt")).exists())
+        .expect("some name should be free");
+
+    let path = dir.join(format!("{name}.txt"));
+    fs::create_dir_all(&dir)
+        .and_then(|_| fs::write(&path, input))
+        .map_err(|err| Error::Fixture {
+            path: path.clone(),
+            message: err.to_string(),
+        })?;
+
+    set_expected(&dir, &name, &Expected::default())?;
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{find_day, fixtures::load_day, Alternative};
+
+    #[test]
+    fn ddmin_finds_the_units_that_matter() {
+        let units: Vec<u32> = (0..100).collect();
+        let shrunk = ddmin(units, |kept| kept.contains(&3) && kept.contains(&71));
+
+        assert_eq!(shrunk, [3, 71]);
+    }
+
+    #[test]
+    fn crashing_network_keeps_its_instructions() {
+        let day = find_day(8).unwrap();
+        let nodes: Vec<String> = (0..40).map(|n| format!("N{n:02} = (AAA, AAA)")).collect();
+        let input = format!("LR\n\nAAA = (N00, N01)\n{}\n", nodes.join("\n"));
+
+        let shrunk = shrink(day, 1, &input, &RunOptions::default()).unwrap();
+
+        assert!(matches!(
+            &shrunk.failure,
+            Failure::Crash { name: "default", reason } if reason.contains("ZZZ should be reachable")
+        ));
+        assert_eq!(shrunk.lines_before, 43);
+        assert_eq!(shrunk.lines_after, 3);
+        assert!(shrunk.input.starts_with("LR\n\n"));
+        assert!(shrunk.input.ends_with('\n'));
+    }
+
+    // Disagrees with the default whenever a line mentions x
+    const QUARRELSOME: Day = Day {
+        day: 99,
+        parts: [|_| Ok("0".to_string()), |_| Ok("0".to_string())],
+        alternatives: &[Alternative {
+            name: "count-x",
+            part: 1,
+            solver: |input| Ok(input.matches('x').count().to_string()),
+        }],
+    };
+
+    #[test]
+    fn disagreement_shrinks_to_one_line() {
+        let input: Vec<String> = (0..50)
+            .map(|n| match n {
+                17 | 33 => format!("line {n} x"),
+                _ => format!("line {n}"),
+            })
+            .collect();
+
+        let shrunk = shrink(&QUARRELSOME, 1, &input.join("\n"), &RunOptions::default()).unwrap();
+
+        assert_eq!(shrunk.failure, Failure::Disagreement);
+        assert!(["line 17 x", "line 33 x"].contains(&shrunk.input.as_str()));
+    }
+
+    #[test]
+    fn inputs_that_pass_are_an_error() {
+        assert!(matches!(
+            shrink(&QUARRELSOME, 1, "line", &RunOptions::default()),
+            Err(Error::Shrink(_))
+        ));
+    }
+
+    #[test]
+    fn fixtures_get_fresh_names() {
+        let root = tempfile::tempdir().unwrap();
+
+        assert_eq!(write_fixture(root.path(), 8, "LR\n").unwrap(), "shrunk");
+        assert_eq!(write_fixture(root.path(), 8, "RL\n").unwrap(), "shrunk-2");
+
+        let fixtures = load_day(root.path(), 8).unwrap();
+        assert_eq!(fixtures.len(), 2);
+        assert_eq!(fixtures[1].input, "RL\n");
+        assert_eq!(fixtures[1].expected, Expected::default());
+    }
+}
@@ -0,0 +1,497 @@
+//! Terminal UI for stepping forwards and backwards through a day's puzzle
+
+use std::{collections::HashMap, io, time::Duration};
+
+use day_03::SchematicNumber;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Paragraph},
+    DefaultTerminal, Frame,
+};
+
+/// A puzzle worked through one step at a time
+pub trait Simulation {
+    fn title(&self) -> String;
+    /// How many steps there are to look at, at least one
+    fn steps(&self) -> usize;
+    fn view(&self, step: usize) -> Text<'static>;
+}
+
+/// The simulation for the days that have one
+pub fn simulation(day: u8, input: &str) -> Option<Box<dyn Simulation>> {
+    match day {
+        3 => Some(Box::new(Schematic::new(input)?)),
+        8 => Some(Box::new(NodeWalk::new(input)?)),
+        10 => Some(Box::new(PipeWalk::new(input)?)),
+        _ => None,
+    }
+}
+
+const CURRENT: Style = Style::new()
+    .fg(Color::Yellow)
+    .add_modifier(Modifier::REVERSED);
+const DONE: Style = Style::new().fg(Color::Green);
+const REJECTED: Style = Style::new().fg(Color::Red);
+const ENCLOSED: Style = Style::new().bg(Color::Blue);
+
+// Day 03's schematic, checking one number at a time for an adjacent symbol
+struct Schematic {
+    rows: Vec<Vec<char>>,
+    numbers: Vec<SchematicNumber>,
+}
+
+impl Schematic {
+    fn new(input: &str) -> Option<Self> {
+        let rows: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+        let numbers = day_03::schematic_numbers(input).ok()?;
+
+        (!numbers.is_empty()).then_some(Self { rows, numbers })
+    }
+}
+
+impl Simulation for Schematic {
+    fn title(&self) -> String {
+        "Day 3: Gear Ratios".to_string()
+    }
+
+    // Step n has checked the first n numbers
+    fn steps(&self) -> usize {
+        self.numbers.len() + 1
+    }
+
+    fn view(&self, step: usize) -> Text<'static> {
+        let mut styles: HashMap<(usize, usize), Style> = HashMap::new();
+        for (n, number) in self.numbers[..step].iter().enumerate() {
+            let style = match (n + 1 == step, number.is_part_number) {
+                (true, _) => CURRENT,
+                (false, true) => DONE,
+                (false, false) => REJECTED,
+            };
+            styles.extend((number.start..=number.end).map(|j| ((number.row, j), style)));
+        }
+
+        let sum: u32 = self.numbers[..step]
+            .iter()
+            .filter(|number| number.is_part_number)
+            .map(|number| number.value)
+            .sum();
+
+        let mut lines = grid(&self.rows, &styles);
+        lines.push(Line::default());
+        lines.push(Line::from(format!("part numbers so far: {sum}")));
+        Text::from(lines)
+    }
+}
+
+fn grid(rows: &[Vec<char>], styles: &HashMap<(usize, usize), Style>) -> Vec<Line<'static>> {
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let spans: Vec<Span> = row
+                .iter()
+                .enumerate()
+                .map(|(j, c)| {
+                    let style = styles.get(&(i, j)).copied().unwrap_or_default();
+                    Span::styled(c.to_string(), style)
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+// Walks longer than this are cut short rather than filling memory
+const MAX_WALK: usize = 100_000;
+
+// Day 08's walk from AAA to ZZZ, one instruction at a time
+struct NodeWalk {
+    directions: Vec<char>,
+    nodes: HashMap<String, (String, String)>,
+    path: Vec<String>,
+}
+
+impl NodeWalk {
+    fn new(input: &str) -> Option<Self> {
+        let (rest, directions) = day_08::direction_instructions(input).ok()?;
+        let (_, nodes) = day_08::nodes(rest).ok()?;
+        let nodes: HashMap<String, (String, String)> = nodes
+            .into_iter()
+            .map(|(tag, (left, right))| (tag.to_string(), (left.to_string(), right.to_string())))
+            .collect();
+
+        let directions: Vec<char> = directions.chars().collect();
+        if directions.is_empty() || !nodes.contains_key("AAA") {
+            return None;
+        }
+
+        let mut path = vec!["AAA".to_string()];
+
+        for direction in directions.iter().cycle().take(MAX_WALK) {
+            let current = path.last().expect("the walk starts at AAA");
+            if current == "ZZZ" {
+                break;
+            }
+
+            let (left, right) = nodes.get(current)?;
+            path.push(match direction {
+                'L' => left.clone(),
+                _ => right.clone(),
+            });
+        }
+
+        Some(Self {
+            directions,
+            nodes,
+            path,
+        })
+    }
+}
+
+impl Simulation for NodeWalk {
+    fn title(&self) -> String {
+        "Day 8: Haunted Wasteland".to_string()
+    }
+
+    fn steps(&self) -> usize {
+        self.path.len()
+    }
+
+    fn view(&self, step: usize) -> Text<'static> {
+        let tag = &self.path[step];
+        let finished = step + 1 == self.path.len();
+        let turn = step % self.directions.len();
+
+        let instructions: Vec<Span> = self
+            .directions
+            .iter()
+            .enumerate()
+            .map(|(i, direction)| match i == turn && !finished {
+                true => Span::styled(direction.to_string(), CURRENT),
+                false => Span::raw(direction.to_string()),
+            })
+            .collect();
+
+        let (left_style, right_style) = match (finished, self.directions[turn]) {
+            (true, _) => (Style::default(), Style::default()),
+            (false, 'L') => (CURRENT, Style::default()),
+            (false, _) => (Style::default(), CURRENT),
+        };
+
+        // The walk can end at a ZZZ without a line of its own
+        let node = match self.nodes.get(tag) {
+            Some((left, right)) => Line::from(vec![
+                Span::styled(tag.clone(), DONE),
+                Span::raw(" = ("),
+                Span::styled(left.clone(), left_style),
+                Span::raw(", "),
+                Span::styled(right.clone(), right_style),
+                Span::raw(")"),
+            ]),
+            None => Line::from(Span::styled(tag.clone(), DONE)),
+        };
+
+        let status = match (finished, tag.as_str()) {
+            (true, "ZZZ") => format!("reached ZZZ after {step} steps"),
+            (true, _) => format!("gave up after {step} steps"),
+            (false, _) => format!("{step} steps taken"),
+        };
+
+        Text::from(vec![
+            Line::from(instructions),
+            Line::default(),
+            node,
+            Line::default(),
+            Line::from(status),
+        ])
+    }
+}
+
+// Day 10's loop, painted outwards from the start one tile in each direction
+// at a time, then the tiles it encloses
+struct PipeWalk {
+    rows: Vec<Vec<char>>,
+    distances: HashMap<(usize, usize), usize>,
+    furthest: usize,
+    enclosed: Vec<(usize, usize)>,
+}
+
+impl PipeWalk {
+    fn new(input: &str) -> Option<Self> {
+        let rows: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+        let map: HashMap<(usize, usize), char> = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| row.iter().enumerate().map(move |(j, c)| ((i, j), *c)))
+            .collect();
+        let start = *map.iter().find(|(_, &tile)| tile == 'S')?.0;
+
+        let distances = day_10::loop_distances(start, &map);
+        let furthest = distances.values().copied().max()?;
+        let enclosed = day_10::enclosed_tiles(&distances, &map);
+
+        Some(Self {
+            rows,
+            distances,
+            furthest,
+            enclosed,
+        })
+    }
+}
+
+impl Simulation for PipeWalk {
+    fn title(&self) -> String {
+        "Day 10: Pipe Maze".to_string()
+    }
+
+    // The last step fills in the enclosed tiles
+    fn steps(&self) -> usize {
+        self.furthest + 2
+    }
+
+    fn view(&self, step: usize) -> Text<'static> {
+        let mut styles: HashMap<(usize, usize), Style> = self
+            .distances
+            .iter()
+            .filter(|(_, &distance)| distance <= step)
+            .map(|(&position, &distance)| match distance == step {
+                true => (position, CURRENT),
+                false => (position, DONE),
+            })
+            .collect();
+
+        let status = if step > self.furthest {
+            styles.extend(self.enclosed.iter().map(|&position| (position, ENCLOSED)));
+            format!("{} tiles enclosed", self.enclosed.len())
+        } else {
+            format!("{step} tiles from the start")
+        };
+
+        let mut lines = grid(&self.rows, &styles);
+        lines.push(Line::default());
+        lines.push(Line::from(status));
+        Text::from(lines)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Forward,
+    Back,
+    First,
+    Last,
+    Quit,
+}
+
+pub fn action(key: KeyEvent) -> Option<Action> {
+    if key.kind != KeyEventKind::Press {
+        return None;
+    }
+
+    match key.code {
+        KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(' ') => Some(Action::Forward),
+        KeyCode::Left | KeyCode::Char('h') => Some(Action::Back),
+        KeyCode::Home | KeyCode::Char('g') => Some(Action::First),
+        KeyCode::End | KeyCode::Char('G') => Some(Action::Last),
+        KeyCode::Esc | KeyCode::Char('q') => Some(Action::Quit),
+        _ => None,
+    }
+}
+
+/// Which step of a simulation is on screen
+pub struct Stepper {
+    simulation: Box<dyn Simulation>,
+    pub step: usize,
+}
+
+impl Stepper {
+    pub fn new(simulation: Box<dyn Simulation>) -> Self {
+        Self {
+            simulation,
+            step: 0,
+        }
+    }
+
+    /// Moves between steps, returning false once the user asks to quit
+    pub fn apply(&mut self, action: Action) -> bool {
+        let last = self.simulation.steps() - 1;
+        self.step = match action {
+            Action::Forward => (self.step + 1).min(last),
+            Action::Back => self.step.saturating_sub(1),
+            Action::First => 0,
+            Action::Last => last,
+            Action::Quit => return false,
+        };
+
+        true
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        let [main, help] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+
+        let block = Block::bordered().title(format!(" {} ", self.simulation.title()));
+        frame.render_widget(
+            Paragraph::new(self.simulation.view(self.step)).block(block),
+            main,
+        );
+
+        let keys = Line::from(format!(
+            "step {}/{}  ←/→ step  home/end jump  q quit",
+            self.step + 1,
+            self.simulation.steps()
+        ));
+        frame.render_widget(keys, help);
+    }
+}
+
+/// Draws the stepper until the user quits, redrawing after every key
+pub fn run(terminal: &mut DefaultTerminal, mut stepper: Stepper) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| stepper.draw(frame))?;
+
+        // Poll rather than block, so a resized terminal gets redrawn
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if let Some(action) = action(key) {
+                if !stepper.apply(action) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::load_day, workspace_root};
+
+    use ratatui::{backend::TestBackend, buffer::Buffer, layout::Rect, Terminal};
+
+    fn example(day: u8) -> String {
+        load_day(workspace_root(), day).unwrap().remove(0).input
+    }
+
+    fn stepper(day: u8) -> Stepper {
+        Stepper::new(simulation(day, &example(day)).unwrap())
+    }
+
+    fn render(stepper: &Stepper, width: u16, height: u16) -> Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|frame| stepper.draw(frame)).unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    #[test]
+    fn node_walk_highlights_the_current_instruction() {
+        let mut stepper = stepper(8);
+        stepper.apply(Action::Forward);
+
+        let frame = render(&stepper, 44, 8);
+        let mut expected = Buffer::with_lines([
+            "┌ Day 8: Haunted Wasteland ────────────────┐",
+            "│LLR                                       │",
+            "│                                          │",
+            "│BBB = (AAA, ZZZ)                          │",
+            "│                                          │",
+            "│1 steps taken                             │",
+            "└──────────────────────────────────────────┘",
+            "step 2/7  ←/→ step  home/end jump  q quit   ",
+        ]);
+        expected.set_style(Rect::new(2, 1, 1, 1), CURRENT);
+        expected.set_style(Rect::new(1, 3, 3, 1), DONE);
+        expected.set_style(Rect::new(8, 3, 3, 1), CURRENT);
+        assert_eq!(frame, expected);
+
+        stepper.apply(Action::Last);
+        let frame = frame_text(&render(&stepper, 44, 8));
+        assert!(frame.contains("reached ZZZ after 6 steps"), "{frame}");
+    }
+
+    #[test]
+    fn node_walk_can_end_at_a_zzz_without_a_line() {
+        let walk = NodeWalk::new("R\n\nAAA = (AAA, ZZZ)\n").unwrap();
+        let mut stepper = Stepper::new(Box::new(walk));
+        stepper.apply(Action::Last);
+
+        let frame = frame_text(&render(&stepper, 30, 8));
+        assert!(frame.contains("│ZZZ "), "{frame}");
+        assert!(frame.contains("reached ZZZ after 1 steps"), "{frame}");
+    }
+
+    fn frame_text(buffer: &Buffer) -> String {
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn pipe_walk_paints_the_visited_tiles() {
+        let mut stepper = stepper(10);
+        stepper.apply(Action::Last);
+        stepper.apply(Action::Back);
+        assert_eq!(stepper.step, 8);
+
+        let frame = render(&stepper, 30, 10);
+        // The start, inside the border, was painted long before the furthest tile
+        assert_eq!(frame[(1, 3)].symbol(), "S");
+        assert_eq!(frame[(1, 3)].fg, Color::Green);
+        assert_eq!(frame[(5, 3)].modifier, Modifier::REVERSED);
+        assert_eq!(frame[(1, 2)].fg, Color::Reset);
+        assert!(frame_text(&frame).contains("8 tiles from the start"));
+
+        stepper.apply(Action::Last);
+        let frame = frame_text(&render(&stepper, 30, 10));
+        assert!(frame.contains("1 tiles enclosed"), "{frame}");
+    }
+
+    #[test]
+    fn schematic_adds_up_the_part_numbers() {
+        let mut stepper = stepper(3);
+        stepper.apply(Action::Forward);
+        stepper.apply(Action::Forward);
+
+        // 467 was a part number, 114 is being checked
+        let frame = render(&stepper, 30, 16);
+        assert_eq!(frame[(1, 1)].fg, Color::Green);
+        assert_eq!(frame[(6, 1)].modifier, Modifier::REVERSED);
+
+        stepper.apply(Action::Forward);
+        let frame = render(&stepper, 30, 16);
+        assert_eq!(frame[(6, 1)].fg, Color::Red);
+
+        stepper.apply(Action::Last);
+        let frame = frame_text(&render(&stepper, 30, 16));
+        assert!(frame.contains("part numbers so far: 4361"), "{frame}");
+    }
+
+    #[test]
+    fn steps_stay_in_range_until_quit() {
+        let mut stepper = stepper(8);
+
+        assert!(stepper.apply(Action::Back));
+        assert_eq!(stepper.step, 0);
+        for _ in 0..20 {
+            stepper.apply(Action::Forward);
+        }
+        assert_eq!(stepper.step, 6);
+        stepper.apply(Action::First);
+        assert_eq!(stepper.step, 0);
+
+        assert!(!stepper.apply(Action::Quit));
+    }
+
+    #[test]
+    fn only_some_days_have_a_simulation() {
+        assert!(simulation(6, &example(6)).is_none());
+        assert!(simulation(8, "no network here").is_none());
+    }
+}
@@ -0,0 +1,165 @@
+//! SVG pictures of a day's puzzle, for the HTML report
+
+use std::{collections::HashMap, f64::consts::TAU, fmt::Write};
+
+use crate::report::escape;
+
+/// A picture of the puzzle for the days that have one
+pub fn visualise(day: u8, input: &str) -> Option<String> {
+    match day {
+        8 => network(input),
+        10 => pipe_loop(input),
+        _ => None,
+    }
+}
+
+fn svg(width: f64, height: f64, body: &str) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {width} {height}\" \
+         width=\"{width}\" height=\"{height}\" style=\"background:#0f0f23\">\n{body}</svg>\n"
+    )
+}
+
+// Day 08's network, with the nodes on a circle. Left turns are blue, right
+// turns orange, and start/end nodes are highlighted.
+fn network(input: &str) -> Option<String> {
+    let (rest, _) = day_08::direction_instructions(input).ok()?;
+    let (_, nodes) = day_08::nodes(rest).ok()?;
+
+    let radius = (nodes.len() as f64 * 4.0).max(120.0);
+    let size = radius * 2.0 + 80.0;
+    let centre = size / 2.0;
+
+    let positions: HashMap<&str, (f64, f64)> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, (tag, _))| {
+            let angle = TAU * i as f64 / nodes.len() as f64;
+            let point = (centre + radius * angle.cos(), centre + radius * angle.sin());
+            (*tag, point)
+        })
+        .collect();
+
+    let mut body = String::new();
+    for (tag, (left, right)) in &nodes {
+        let from = positions[tag];
+        for (to, colour) in [(left, "#5b8def"), (right, "#f2a03d")] {
+            if let Some(to) = positions.get(to) {
+                let _ = writeln!(
+                    body,
+                    "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{colour}\" stroke-opacity=\"0.5\"/>",
+                    from.0, from.1, to.0, to.1
+                );
+            }
+        }
+    }
+
+    for (tag, _) in &nodes {
+        let (x, y) = positions[tag];
+        let fill = match tag.as_bytes()[2] {
+            b'A' => "#00cc00",
+            b'Z' => "#ff4040",
+            _ => "#cccccc",
+        };
+        let _ = writeln!(
+            body,
+            "<circle cx=\"{x:.1}\" cy=\"{y:.1}\" r=\"3\" fill=\"{fill}\"><title>{}</title></circle>",
+            escape(tag)
+        );
+    }
+
+    Some(svg(size, size, &body))
+}
+
+// Day 10's main loop drawn as pipes, with the tiles it encloses filled in
+fn pipe_loop(input: &str) -> Option<String> {
+    const CELL: f64 = 8.0;
+
+    let map: HashMap<(usize, usize), char> = input
+        .lines()
+        .enumerate()
+        .flat_map(|(i, line)| line.chars().enumerate().map(move |(j, c)| ((i, j), c)))
+        .collect();
+    let start = *map.iter().find(|(_, &tile)| tile == 'S')?.0;
+
+    let rows = input.lines().count();
+    let cols = input.lines().map(str::len).max()?;
+    let path = day_10::loop_distances(start, &map);
+
+    let centre = |(i, j): (usize, usize)| ((j as f64 + 0.5) * CELL, (i as f64 + 0.5) * CELL);
+
+    let mut tiles: Vec<(usize, usize)> = path.keys().copied().collect();
+    tiles.sort_unstable();
+
+    let mut body = String::new();
+    for position in tiles {
+        let (x1, y1) = centre(position);
+        for next in day_10::connected_tiles(position, &map) {
+            let (x2, y2) = centre(next);
+            let _ = writeln!(
+                body,
+                "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#ffff66\" stroke-width=\"2\"/>",
+                (x1 + x2) / 2.0,
+                (y1 + y2) / 2.0
+            );
+        }
+    }
+
+    for (i, j) in day_10::enclosed_tiles(&path, &map) {
+        let _ = writeln!(
+            body,
+            "<rect x=\"{}\" y=\"{}\" width=\"{CELL}\" height=\"{CELL}\" fill=\"#009900\"/>",
+            j as f64 * CELL,
+            i as f64 * CELL
+        );
+    }
+
+    let (x, y) = centre(start);
+    let _ = writeln!(
+        body,
+        "<circle cx=\"{x}\" cy=\"{y}\" r=\"{}\" fill=\"#ff4040\"/>",
+        CELL / 2.0
+    );
+
+    Some(svg(cols as f64 * CELL, rows as f64 * CELL, &body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pipe_loop_fills_enclosed_tiles() {
+        let input = "...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
+
+        let picture = visualise(10, input).unwrap();
+        assert!(picture.starts_with("<svg"));
+        assert_eq!(picture.matches("<rect").count(), 4);
+    }
+
+    #[test]
+    fn network_draws_every_node() {
+        let input = "LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)";
+
+        let picture = visualise(8, input).unwrap();
+        assert_eq!(picture.matches("<circle").count(), 3);
+        assert_eq!(picture.matches("<line").count(), 6);
+    }
+
+    #[test]
+    fn other_days_have_no_picture() {
+        assert_eq!(visualise(6, "Time: 7\nDistance: 9"), None);
+    }
+}
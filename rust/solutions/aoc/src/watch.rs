@@ -0,0 +1,311 @@
+use std::{
+    env, fmt,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::mpsc,
+    time::Duration,
+};
+
+use notify::{RecursiveMode, Watcher};
+
+use crate::{
+    day_dir, find_day, fixtures, input_path,
+    outcome::{run_day, Outcome},
+    plugin::{self, Reloader},
+    Error, RunOptions,
+};
+
+// Editors tend to write a file several times when saving, so wait for things
+// to go quiet before rebuilding
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Added(Outcome),
+    Changed { before: Outcome, after: Outcome },
+    Removed(Outcome),
+}
+
+fn label(outcome: &Outcome) -> String {
+    format!(
+        "day {:02} part {} {}",
+        outcome.day, outcome.part, outcome.source
+    )
+}
+
+fn result(outcome: &Outcome) -> &str {
+    match (&outcome.error, &outcome.answer) {
+        (Some(error), _) => error,
+        (None, Some(answer)) => answer,
+        (None, None) => "no answer",
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Added(outcome) => write!(f, "{outcome}"),
+            Change::Changed { before, after } => {
+                write!(
+                    f,
+                    "{}: {} -> {}",
+                    label(after),
+                    result(before),
+                    result(after)
+                )?;
+                match after.expected.as_deref() {
+                    Some(_) if after.passed() => write!(f, " ok")?,
+                    Some(expected) => write!(f, ", expected {expected}")?,
+                    None => {}
+                }
+                match &after.warning {
+                    Some(warning) => write!(f, "\n  WARNING: {warning}, don't submit it"),
+                    None => Ok(()),
+                }
+            }
+            Change::Removed(outcome) => write!(f, "{}: gone", label(outcome)),
+        }
+    }
+}
+
+fn same_result(a: &Outcome, b: &Outcome) -> bool {
+    a.answer == b.answer && a.error == b.error && a.expected == b.expected && a.warning == b.warning
+}
+
+/// Compares two runs of the same day by part and source, ignoring timings
+pub fn diff(previous: &[Outcome], current: &[Outcome]) -> Vec<Change> {
+    let key = |o: &Outcome| (o.part, o.source.clone());
+
+    let mut changes = Vec::new();
+    for after in current {
+        match previous.iter().find(|before| key(before) == key(after)) {
+            None => changes.push(Change::Added(after.clone())),
+            Some(before) if !same_result(before, after) => changes.push(Change::Changed {
+                before: before.clone(),
+                after: after.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    for before in previous {
+        if !current.iter().any(|after| key(after) == key(before)) {
+            changes.push(Change::Removed(before.clone()));
+        }
+    }
+
+    changes
+}
+
+fn watched_paths(root: &Path, day: u8) -> Vec<PathBuf> {
+    [
+        day_dir(root, day).join("src"),
+        input_path(root, day),
+        fixtures::day_fixtures_dir(root, day),
+    ]
+    .into_iter()
+    .filter(|path| path.exists())
+    .collect()
+}
+
+// The solutions have to be recompiled to pick up edits, so each round runs the
+// runner through cargo. A separate target dir keeps it from fighting over the
+// build lock with an editor's cargo check.
+fn run_once(root: &Path, day: u8) -> Result<Vec<Outcome>, String> {
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = Command::new(cargo)
+        .current_dir(root)
+        .args(["run", "-q", "-p", "aoc", "--target-dir"])
+        .arg(root.join("target").join("watch"))
+        .args(["--", "run", &day.to_string(), "--examples", "--json"])
+        .output()
+        .map_err(|err| format!("could not start cargo: {err}"))?;
+
+    let outcomes: Vec<Outcome> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    // Failing examples also exit non-zero, so only a run with nothing to show
+    // counts as broken
+    if outcomes.is_empty() && !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+
+    Ok(outcomes)
+}
+
+// Builds only the day, as a plugin, and swaps it into this process, which is
+// much quicker than rebuilding the runner with every other day in it
+fn run_plugin(root: &Path, day: u8, reloader: &mut Reloader) -> Result<Vec<Outcome>, String> {
+    plugin::build(root, day)?;
+    reloader.reload().map_err(|err| err.to_string())?;
+    let day = reloader
+        .day()
+        .expect("a reload should leave the day loaded");
+
+    // The same as `aoc run --examples` would do
+    let input = input_path(root, day.day);
+    let options = RunOptions {
+        timeout: Some(Duration::from_secs(60)),
+        ..RunOptions::default()
+    };
+    run_day(
+        root,
+        day,
+        &[1, 2],
+        input.exists().then_some(input.as_path()),
+        true,
+        &options,
+    )
+    .map_err(|err| err.to_string())
+}
+
+fn report(previous: &mut Option<Vec<Outcome>>, result: Result<Vec<Outcome>, String>) {
+    let current = match result {
+        Ok(current) => current,
+        Err(stderr) => {
+            eprintln!("{}", stderr.trim_end());
+            println!("build failed, waiting for changes");
+            return;
+        }
+    };
+
+    match previous {
+        None => current.iter().for_each(|outcome| println!("{outcome}")),
+        Some(previous) => {
+            let changes = diff(previous, &current);
+            changes.iter().for_each(|change| println!("{change}"));
+
+            let unchanged = current.len() - changes.len().min(current.len());
+            println!("{} changed, {unchanged} unchanged", changes.len());
+        }
+    }
+
+    let failing = current.iter().filter(|outcome| !outcome.passed()).count();
+    if failing > 0 {
+        println!("{failing} failing");
+    }
+
+    *previous = Some(current);
+}
+
+/// Reruns a day's examples and input whenever its source, input or fixtures
+/// change, printing only what moved since the last run. With `plugin`, the
+/// day is rebuilt on its own and loaded into this process rather than run
+/// through a rebuilt runner, so it needn't be a day the runner knows about.
+pub fn watch(root: &Path, day: u8, plugin: bool) -> Result<(), Error> {
+    if !plugin {
+        find_day(day)?;
+    }
+    let mut reloader = plugin.then(|| Reloader::new(plugin::library_path(root, day)));
+    let mut run = || match &mut reloader {
+        Some(reloader) => run_plugin(root, day, reloader),
+        None => run_once(root, day),
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).map_err(|err| Error::Watch(err.to_string()))?;
+
+    let paths = watched_paths(root, day);
+    for path in &paths {
+        watcher
+            .watch(path, RecursiveMode::Recursive)
+            .map_err(|err| Error::Watch(format!("{}: {err}", path.display())))?;
+    }
+
+    println!(
+        "watching {}",
+        paths
+            .iter()
+            .map(|path| path
+                .strip_prefix(root)
+                .unwrap_or(path)
+                .display()
+                .to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let mut previous = None;
+    report(&mut previous, run());
+
+    loop {
+        let event: notify::Result<notify::Event> = rx
+            .recv()
+            .map_err(|_| Error::Watch("file watcher stopped".to_string()))?;
+        if event.is_ok_and(|event| event.kind.is_access()) {
+            continue;
+        }
+
+        while rx.recv_timeout(DEBOUNCE).is_ok() {}
+
+        println!();
+        report(&mut previous, run());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outcome::INPUT;
+
+    fn outcome(part: u8, source: &str, answer: &str, expected: Option<&str>) -> Outcome {
+        Outcome {
+            day: 5,
+            part,
+            source: source.to_string(),
+            answer: Some(answer.to_string()),
+            error: None,
+            expected: expected.map(str::to_string),
+            elapsed_ms: 1.0,
+            allocations: None,
+            warning: None,
+        }
+    }
+
+    #[test]
+    fn diff_ignores_timing() {
+        let before = [outcome(1, INPUT, "35", None)];
+        let mut after = before.clone();
+        after[0].elapsed_ms = 20.0;
+
+        assert_eq!(diff(&before, &after), []);
+    }
+
+    #[test]
+    fn diff_reports_changed_added_and_removed() {
+        let before = [
+            outcome(1, "example", "35", Some("35")),
+            outcome(2, INPUT, "46", None),
+        ];
+        let after = [
+            outcome(2, INPUT, "47", None),
+            outcome(2, "example", "46", Some("46")),
+        ];
+
+        let changes = diff(&before, &after);
+        let shown: Vec<String> = changes.iter().map(ToString::to_string).collect();
+
+        assert_eq!(
+            shown,
+            [
+                "day 05 part 2 input: 46 -> 47",
+                "day 05 part 2 [example]: 46 ok (1.00ms)",
+                "day 05 part 1 example: gone",
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_shows_a_fixture_that_started_failing() {
+        let before = [outcome(1, "example", "35", Some("35"))];
+        let after = [outcome(1, "example", "36", Some("35"))];
+
+        assert_eq!(
+            diff(&before, &after)[0].to_string(),
+            "day 05 part 1 example: 35 -> 36, expected 35"
+        );
+    }
+}
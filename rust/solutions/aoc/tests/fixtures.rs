@@ -0,0 +1,58 @@
+use aoc::{find_day, fixtures, solve, workspace_root, RunOptions};
+
+// Runs every example under tests/fixtures against every implementation of
+// each part, alternatives included. Add a new example by dropping a .txt file
+// into tests/fixtures/day-XX and giving it an entry in that folder's
+// answers.toml.
+#[test]
+fn fixtures_match_expected_answers() {
+    let fixtures = fixtures::load_all(workspace_root()).expect("fixtures should load");
+    assert!(!fixtures.is_empty(), "no fixtures found");
+
+    let mut failures = Vec::new();
+
+    for fixture in &fixtures {
+        let day = find_day(fixture.day).expect("fixture day should be registered");
+
+        // A fixture saved by `shrink` waits for someone to fill in its answers
+        if fixture.expected.part1.is_none() && fixture.expected.part2.is_none() {
+            failures.push(format!(
+                "day {:02} {}: no expected answers in answers.toml",
+                fixture.day, fixture.name
+            ));
+            continue;
+        }
+
+        for part in [1, 2] {
+            let Some(expected) = fixture.expected.part(part) else {
+                continue;
+            };
+
+            let implementations = day
+                .implementations(part)
+                .expect("parts 1 and 2 should exist");
+
+            for (name, _) in implementations {
+                let label = format!(
+                    "day {:02} part {part} {} ({name})",
+                    fixture.day, fixture.name
+                );
+                let options = RunOptions {
+                    implementation: Some(name.to_string()),
+                    ..RunOptions::default()
+                };
+
+                match solve(day, part, &fixture.input, &options) {
+                    Ok(answer) if answer.answer == expected => println!("{label}: ok"),
+                    Ok(answer) => failures.push(format!(
+                        "{label}: expected {expected}, got {}",
+                        answer.answer
+                    )),
+                    Err(err) => failures.push(format!("{label}: {err}")),
+                }
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
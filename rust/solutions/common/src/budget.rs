@@ -0,0 +1,164 @@
+//! Cooperative limits for long running solvers.
+//!
+//! A runner installs a [`Budget`] on the thread running a solver with
+//! [`with_budget`], and long loops call [`tick`] once per iteration. When the
+//! budget's token is cancelled, or its step limit is reached, `tick` unwinds
+//! with a [`Cancelled`] payload that the runner can catch and report. Without a
+//! budget installed `tick` only counts steps, so solvers behave as before.
+
+use std::{
+    cell::{Cell, RefCell},
+    panic,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// Shared flag for asking a solver on another thread to stop
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Budget {
+    pub token: CancelToken,
+    pub max_steps: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// The token was cancelled, usually because the runner's timeout expired
+    Cancelled,
+    /// The solver used up its step limit
+    OutOfSteps,
+}
+
+/// Unwind payload used by [`tick`] to stop a solver
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled {
+    pub reason: Reason,
+    pub steps: u64,
+}
+
+thread_local! {
+    static BUDGET: RefCell<Option<Budget>> = const { RefCell::new(None) };
+    static STEPS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Runs `f` with `budget` installed on this thread, starting the step count
+/// from zero
+pub fn with_budget<T>(budget: Budget, f: impl FnOnce() -> T) -> T {
+    // Restores the previous budget even if `f` unwinds
+    struct Restore(Option<Budget>, u64);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            BUDGET.with(|budget| *budget.borrow_mut() = self.0.take());
+            STEPS.with(|steps| steps.set(self.1));
+        }
+    }
+
+    let previous = BUDGET.with(|current| current.borrow_mut().replace(budget));
+    let _restore = Restore(previous, STEPS.with(|steps| steps.replace(0)));
+
+    f()
+}
+
+/// Steps counted on this thread since the budget was installed
+pub fn steps() -> u64 {
+    STEPS.with(Cell::get)
+}
+
+/// Counts one step of work, and stops the solver if it's been cancelled or is
+/// out of steps. Call it once per iteration of any loop that could run for a
+/// long time on bad input.
+pub fn tick() {
+    let steps = STEPS.with(|steps| {
+        steps.set(steps.get() + 1);
+        steps.get()
+    });
+
+    let reason = BUDGET.with(|budget| {
+        let budget = budget.borrow();
+        let budget = budget.as_ref()?;
+
+        if budget.token.is_cancelled() {
+            Some(Reason::Cancelled)
+        } else if budget.max_steps.is_some_and(|max| steps > max) {
+            Some(Reason::OutOfSteps)
+        } else {
+            None
+        }
+    });
+
+    if let Some(reason) = reason {
+        // Skips the panic hook, as this isn't a bug in the solver
+        panic::resume_unwind(Box::new(Cancelled { reason, steps }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stopped(budget: Budget) -> Cancelled {
+        let payload = panic::catch_unwind(|| {
+            with_budget(budget, || loop {
+                tick();
+            })
+        })
+        .unwrap_err();
+
+        *payload.downcast::<Cancelled>().unwrap()
+    }
+
+    #[test]
+    fn tick_stops_after_the_step_limit() {
+        let budget = Budget {
+            max_steps: Some(100),
+            ..Budget::default()
+        };
+
+        assert_eq!(
+            stopped(budget),
+            Cancelled {
+                reason: Reason::OutOfSteps,
+                steps: 101
+            }
+        );
+    }
+
+    #[test]
+    fn tick_stops_once_cancelled() {
+        let budget = Budget::default();
+        budget.token.cancel();
+
+        assert_eq!(stopped(budget).reason, Reason::Cancelled);
+    }
+
+    #[test]
+    fn steps_are_scoped_to_the_budget() {
+        tick();
+        let inner = with_budget(Budget::default(), || {
+            (0..10).for_each(|_| tick());
+            steps()
+        });
+
+        assert_eq!(inner, 10);
+        assert_eq!(steps(), 1);
+    }
+}
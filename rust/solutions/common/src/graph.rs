@@ -0,0 +1,535 @@
+//! Graph searches over implicit graphs. Nodes are any hashable value and edges
+//! come from a closure, so a graph can be a `HashMap` of adjacency lists (see
+//! [`neighbours_from_map`]), a grid, or the states of a simulation.
+//!
+//! Every search calls [`budget::tick`] per node it expands, so a runaway search
+//! stops when the runner's budget does.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+use crate::budget;
+
+/// Adjacency closure for a graph stored as a map of node -> neighbours.
+/// Nodes missing from the map have no neighbours.
+pub fn neighbours_from_map<N>(map: &HashMap<N, Vec<N>>) -> impl FnMut(&N) -> Vec<N> + '_
+where
+    N: Clone + Eq + Hash,
+{
+    |node| map.get(node).cloned().unwrap_or_default()
+}
+
+/// Breadth-first distances (in edges) from `start` to every reachable node
+pub fn bfs<N, I>(start: N, mut neighbours: impl FnMut(&N) -> I) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        budget::tick();
+        let distance = distances[&node];
+        for next in neighbours(&node) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+/// Shortest path (in edges) from `start` to the first node satisfying
+/// `is_goal`, including both ends. `None` if no goal is reachable, so this is
+/// also a safe way to walk a deterministic machine that might never finish.
+pub fn bfs_path<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents: HashMap<N, Option<N>> = HashMap::from([(start.clone(), None)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        budget::tick();
+        if is_goal(&node) {
+            let mut path = vec![node.clone()];
+            while let Some(Some(parent)) = parents.get(path.last().unwrap()) {
+                path.push(parent.clone());
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for next in neighbours(&node) {
+            if !parents.contains_key(&next) {
+                parents.insert(next.clone(), Some(node.clone()));
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// Depth-first preorder of the nodes reachable from `start`
+pub fn dfs<N, I>(start: N, mut neighbours: impl FnMut(&N) -> I) -> Vec<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = HashSet::new();
+    let mut order = Vec::new();
+    let mut stack = vec![start];
+
+    while let Some(node) = stack.pop() {
+        budget::tick();
+        if !seen.insert(node.clone()) {
+            continue;
+        }
+
+        // Push in reverse so neighbours are visited in the order they're given
+        let next: Vec<N> = neighbours(&node).into_iter().collect();
+        stack.extend(next.into_iter().rev().filter(|n| !seen.contains(n)));
+        order.push(node);
+    }
+
+    order
+}
+
+/// Cheapest path from `start` to a goal, with the total cost
+pub fn dijkstra<N, C, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// Cheapest path from `start` to a goal, guided by a `heuristic` which must
+/// never overestimate the remaining cost
+pub fn astar<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    // Nodes are stored once and referred to by index, so N doesn't need Ord
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    let mut index = HashMap::from([(start.clone(), 0)]);
+    let mut nodes = vec![start];
+    let mut parents: Vec<Option<usize>> = vec![None];
+    let mut costs = vec![C::default()];
+
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        budget::tick();
+        if cost > costs[i] {
+            continue;
+        }
+
+        if is_goal(&nodes[i]) {
+            let mut path = vec![nodes[i].clone()];
+            let mut current = i;
+            while let Some(parent) = parents[current] {
+                path.push(nodes[parent].clone());
+                current = parent;
+            }
+            path.reverse();
+            return Some((path, cost));
+        }
+
+        for (next, step) in successors(&nodes[i].clone()) {
+            let next_cost = cost + step;
+            let j = match index.get(&next) {
+                Some(&j) if costs[j] <= next_cost => continue,
+                Some(&j) => {
+                    costs[j] = next_cost;
+                    parents[j] = Some(i);
+                    j
+                }
+                None => {
+                    index.insert(next.clone(), nodes.len());
+                    nodes.push(next);
+                    parents.push(Some(i));
+                    costs.push(next_cost);
+                    nodes.len() - 1
+                }
+            };
+            heap.push(Reverse((next_cost + heuristic(&nodes[j]), next_cost, j)));
+        }
+    }
+
+    None
+}
+
+/// Where a deterministic sequence `x0, f(x0), f(f(x0)), ...` starts repeating:
+/// `start` is the index of the first repeated state, `length` the period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+/// Floyd's tortoise and hare, using constant memory
+pub fn floyd<T: Clone + PartialEq>(x0: T, f: impl Fn(&T) -> T) -> Cycle {
+    let mut tortoise = f(&x0);
+    let mut hare = f(&tortoise);
+    while tortoise != hare {
+        budget::tick();
+        tortoise = f(&tortoise);
+        hare = f(&f(&hare));
+    }
+
+    let mut start = 0;
+    tortoise = x0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = f(&tortoise);
+    while tortoise != hare {
+        hare = f(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Brent's algorithm, which usually calls `f` fewer times than [`floyd`]
+pub fn brent<T: Clone + PartialEq>(x0: T, f: impl Fn(&T) -> T) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = x0.clone();
+    let mut hare = f(&x0);
+    while tortoise != hare {
+        budget::tick();
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = f(&hare);
+        length += 1;
+    }
+
+    tortoise = x0.clone();
+    hare = x0;
+    for _ in 0..length {
+        hare = f(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Disjoint sets over `0..n`, with path compression and union by size
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    pub fn new(n: usize) -> Self {
+        UnionFind {
+            parents: (0..n).collect(),
+            sizes: vec![1; n],
+            sets: n,
+        }
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        let mut current = x;
+        while self.parents[current] != root {
+            current = std::mem::replace(&mut self.parents[current], root);
+        }
+
+        root
+    }
+
+    /// Merges the sets holding `a` and `b`, returning false if they were already one
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (big, small) = if self.sizes[a] >= self.sizes[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parents[small] = big;
+        self.sizes[big] += self.sizes[small];
+        self.sets -= 1;
+        true
+    }
+
+    /// Size of the set holding `x`
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.sizes[root]
+    }
+
+    /// Number of disjoint sets
+    pub fn sets(&self) -> usize {
+        self.sets
+    }
+}
+
+/// Groups `nodes` into connected components, treating edges as undirected and
+/// ignoring edges to nodes outside `nodes`. Components are ordered by their
+/// first node, and keep the given node order.
+pub fn connected_components<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> Vec<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut index: HashMap<N, usize> = HashMap::new();
+    let mut unique = Vec::new();
+    for node in nodes {
+        if !index.contains_key(&node) {
+            index.insert(node.clone(), unique.len());
+            unique.push(node);
+        }
+    }
+
+    let mut sets = UnionFind::new(unique.len());
+    for (i, node) in unique.iter().enumerate() {
+        for next in neighbours(node) {
+            if let Some(&j) = index.get(&next) {
+                sets.union(i, j);
+            }
+        }
+    }
+
+    let mut components: Vec<Vec<N>> = Vec::new();
+    let mut component_of_root = HashMap::new();
+    for (i, node) in unique.into_iter().enumerate() {
+        let component = *component_of_root.entry(sets.find(i)).or_insert_with(|| {
+            components.push(Vec::new());
+            components.len() - 1
+        });
+        components[component].push(node);
+    }
+
+    components
+}
+
+/// Orders `nodes` so every node comes before its successors (Kahn's
+/// algorithm). If the graph has a cycle, returns `Err` with a node on it.
+pub fn topological_sort<N, I>(nodes: &[N], mut successors: impl FnMut(&N) -> I) -> Result<Vec<N>, N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut edges: HashMap<N, Vec<N>> = HashMap::new();
+    let mut in_degree: HashMap<N, usize> = nodes.iter().map(|n| (n.clone(), 0)).collect();
+
+    for node in nodes {
+        let next: Vec<N> = successors(node).into_iter().collect();
+        for n in &next {
+            *in_degree.entry(n.clone()).or_default() += 1;
+        }
+        edges.insert(node.clone(), next);
+    }
+
+    // Seed in the given order so the result is deterministic
+    let mut queue: VecDeque<N> = nodes
+        .iter()
+        .filter(|n| in_degree[*n] == 0)
+        .cloned()
+        .collect();
+    let mut order = Vec::new();
+
+    while let Some(node) = queue.pop_front() {
+        for next in edges.get(&node).into_iter().flatten() {
+            let degree = in_degree.get_mut(next).unwrap();
+            *degree -= 1;
+            if *degree == 0 {
+                queue.push_back(next.clone());
+            }
+        }
+        order.push(node);
+    }
+
+    if order.len() < in_degree.len() {
+        let on_cycle = in_degree
+            .into_iter()
+            .find(|(_, degree)| *degree > 0)
+            .map(|(node, _)| node)
+            .unwrap();
+        return Err(on_cycle);
+    }
+
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> HashMap<char, Vec<char>> {
+        HashMap::from([
+            ('a', vec!['b', 'c']),
+            ('b', vec!['d']),
+            ('c', vec!['d']),
+            ('d', vec!['e']),
+            ('x', vec!['y']),
+        ])
+    }
+
+    #[test]
+    fn bfs_finds_distances_and_paths() {
+        let map = graph();
+        let distances = bfs('a', neighbours_from_map(&map));
+
+        assert_eq!(distances.len(), 5);
+        assert_eq!(distances[&'e'], 3);
+
+        let path = bfs_path('a', neighbours_from_map(&map), |n| *n == 'd');
+        assert_eq!(path, Some(vec!['a', 'b', 'd']));
+        assert_eq!(
+            bfs_path('a', neighbours_from_map(&map), |n| *n == 'x'),
+            None
+        );
+    }
+
+    #[test]
+    fn dfs_visits_in_neighbour_order() {
+        let map = graph();
+        assert_eq!(
+            dfs('a', neighbours_from_map(&map)),
+            ['a', 'b', 'd', 'e', 'c']
+        );
+    }
+
+    #[test]
+    fn dijkstra_and_astar_find_the_cheapest_path() {
+        // Grid where stepping onto a '#' costs 9
+        let grid = ["..#.", ".##.", "...."];
+        let successors = |&(r, c): &(i32, i32)| {
+            [(0, 1), (1, 0), (0, -1), (-1, 0)]
+                .into_iter()
+                .map(move |(dr, dc)| (r + dr, c + dc))
+                .filter(|&(r, c)| (0..3).contains(&r) && (0..4).contains(&c))
+                .map(|(r, c)| {
+                    let cost = if grid[r as usize].as_bytes()[c as usize] == b'#' {
+                        9
+                    } else {
+                        1
+                    };
+                    ((r, c), cost)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let (path, cost) = dijkstra((0, 0), successors, |&n| n == (0, 3)).unwrap();
+        assert_eq!(cost, 7);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(0, 3)));
+
+        let manhattan = |&(r, c): &(i32, i32)| r.abs() + (3 - c).abs();
+        let (_, astar_cost) = astar((0, 0), successors, manhattan, |&n| n == (0, 3)).unwrap();
+        assert_eq!(astar_cost, 7);
+    }
+
+    #[test]
+    fn floyd_and_brent_agree() {
+        // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 3 ...
+        let f = |x: &u32| if *x == 5 { 3 } else { x + 1 };
+        let expected = Cycle {
+            start: 3,
+            length: 3,
+        };
+
+        assert_eq!(floyd(0, f), expected);
+        assert_eq!(brent(0, f), expected);
+        assert_eq!(
+            brent(3, f),
+            Cycle {
+                start: 0,
+                length: 3
+            }
+        );
+    }
+
+    #[test]
+    fn union_find_tracks_sets() {
+        let mut sets = UnionFind::new(5);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(3, 4));
+        assert!(!sets.union(1, 0));
+        assert!(sets.union(1, 4));
+
+        assert_eq!(sets.sets(), 2);
+        assert_eq!(sets.size(0), 4);
+        assert_eq!(sets.find(3), sets.find(0));
+    }
+
+    #[test]
+    fn connected_components_ignore_edge_direction() {
+        let map = graph();
+        let components = connected_components(
+            ['a', 'b', 'c', 'd', 'e', 'x', 'y', 'z'],
+            neighbours_from_map(&map),
+        );
+
+        assert_eq!(
+            components,
+            [vec!['a', 'b', 'c', 'd', 'e'], vec!['x', 'y'], vec!['z']]
+        );
+    }
+
+    #[test]
+    fn topological_sort_orders_or_reports_a_cycle() {
+        let map = graph();
+        let nodes = ['a', 'b', 'c', 'd', 'e'];
+        assert_eq!(
+            topological_sort(&nodes, neighbours_from_map(&map)),
+            Ok(vec!['a', 'b', 'c', 'd', 'e'])
+        );
+
+        let cyclic = HashMap::from([('a', vec!['b']), ('b', vec!['c']), ('c', vec!['a'])]);
+        assert!(topological_sort(&['a', 'b', 'c'], neighbours_from_map(&cyclic)).is_err());
+    }
+}
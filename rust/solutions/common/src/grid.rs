@@ -0,0 +1,286 @@
+//! A sparse grid on signed coordinates, for maps that grow in every direction
+//! or repeat forever. Points are `(row, column)` like the `usize` grids in the
+//! day crates, but can go negative, so stepping off the top or left edge is
+//! just another point rather than a `None`.
+
+use std::{collections::HashMap, fmt};
+
+pub type Point = (i64, i64);
+
+pub const UP: Point = (-1, 0);
+pub const DOWN: Point = (1, 0);
+pub const LEFT: Point = (0, -1);
+pub const RIGHT: Point = (0, 1);
+
+pub fn add((row, col): Point, (d_row, d_col): Point) -> Point {
+    (row + d_row, col + d_col)
+}
+
+/// The four points sharing an edge with `point`
+pub fn neighbours(point: Point) -> impl Iterator<Item = Point> {
+    [UP, DOWN, LEFT, RIGHT]
+        .into_iter()
+        .map(move |step| add(point, step))
+}
+
+/// An inclusive rectangle of points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    pub fn point(point: Point) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    pub fn contains(&self, (row, col): Point) -> bool {
+        (self.min.0..=self.max.0).contains(&row) && (self.min.1..=self.max.1).contains(&col)
+    }
+
+    /// The smallest bounds holding these and `point`
+    pub fn including(&self, (row, col): Point) -> Self {
+        Self {
+            min: (self.min.0.min(row), self.min.1.min(col)),
+            max: (self.max.0.max(row), self.max.1.max(col)),
+        }
+    }
+
+    pub fn height(&self) -> i64 {
+        self.max.0 - self.min.0 + 1
+    }
+
+    pub fn width(&self) -> i64 {
+        self.max.1 - self.min.1 + 1
+    }
+
+    /// The point inside these bounds that `point` lands on when the bounds are
+    /// repeated across the whole plane
+    pub fn wrap(&self, (row, col): Point) -> Point {
+        (
+            self.min.0 + (row - self.min.0).rem_euclid(self.height()),
+            self.min.1 + (col - self.min.1).rem_euclid(self.width()),
+        )
+    }
+}
+
+/// Values at points, with the bounding box of the occupied points kept up to
+/// date. A grid read from text also remembers the text's extent as its tile,
+/// for maps that repeat in every direction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    bounds: Option<Bounds>,
+    tile: Option<Bounds>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bounds: None,
+            tile: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a grid of text, with the first character at `(0, 0)`. Characters
+    /// `cell` turns into `None`, usually the background, are left empty.
+    pub fn from_text(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Self {
+        let mut grid = Self::new();
+        let mut extent: Option<Bounds> = None;
+
+        for (row, line) in input.lines().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                let point = (row as i64, col as i64);
+                extent = Some(extent.map_or(Bounds::point(point), |e| e.including(point)));
+                if let Some(value) = cell(c) {
+                    grid.insert(point, value);
+                }
+            }
+        }
+
+        grid.tile = extent;
+        grid
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The smallest rectangle holding every occupied point, if there are any
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    /// The repeating part of the map: the extent of the text it was read from,
+    /// unless set otherwise
+    pub fn tile(&self) -> Option<Bounds> {
+        self.tile
+    }
+
+    pub fn set_tile(&mut self, tile: Bounds) {
+        self.tile = Some(tile);
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    /// Looks `point` up as if the tile were repeated across the whole plane.
+    /// A grid with no tile only has its own cells.
+    pub fn get_tiled(&self, point: Point) -> Option<&T> {
+        match self.tile {
+            Some(tile) => self.get(tile.wrap(point)),
+            None => self.get(point),
+        }
+    }
+
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.bounds = Some(
+            self.bounds
+                .map_or(Bounds::point(point), |b| b.including(point)),
+        );
+        self.cells.insert(point, value)
+    }
+
+    pub fn remove(&mut self, point: Point) -> Option<T> {
+        let value = self.cells.remove(&point)?;
+
+        // Only a point on the edge can pull the bounds in
+        let on_edge = self.bounds.is_some_and(|b| {
+            point.0 == b.min.0 || point.0 == b.max.0 || point.1 == b.min.1 || point.1 == b.max.1
+        });
+        if on_edge {
+            self.bounds = self.cells.keys().fold(None, |bounds, &p| {
+                Some(bounds.map_or(Bounds::point(p), |b: Bounds| b.including(p)))
+            });
+        }
+
+        Some(value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(&point, value)| (point, value))
+    }
+
+    /// Draws the occupied region a row per line, with `cell` choosing each
+    /// point's character from what's there
+    pub fn render(&self, mut cell: impl FnMut(Option<&T>) -> char) -> String {
+        let Some(bounds) = self.bounds else {
+            return String::new();
+        };
+
+        let mut text = String::new();
+        for row in bounds.min.0..=bounds.max.0 {
+            text.extend((bounds.min.1..=bounds.max.1).map(|col| cell(self.get((row, col)))));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        for (point, value) in iter {
+            grid.insert(point, value);
+        }
+        grid
+    }
+}
+
+/// Empty points are drawn as `.`
+impl fmt::Display for SparseGrid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(|cell| cell.copied().unwrap_or('.')))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_follow_the_occupied_points() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        grid.insert((0, 0), '#');
+        grid.insert((-3, 2), '#');
+        grid.insert((1, -4), '#');
+        assert_eq!(
+            grid.bounds(),
+            Some(Bounds {
+                min: (-3, -4),
+                max: (1, 2)
+            })
+        );
+
+        grid.remove((-3, 2));
+        assert_eq!(
+            grid.bounds(),
+            Some(Bounds {
+                min: (0, -4),
+                max: (1, 0)
+            })
+        );
+
+        grid.remove((0, 0));
+        grid.remove((1, -4));
+        assert_eq!(grid.bounds(), None);
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn tiled_lookups_repeat_the_map() {
+        let grid = SparseGrid::from_text("#..\n.#.\n", |c| (c == '#').then_some(c));
+
+        assert_eq!(grid.len(), 2);
+        assert_eq!(
+            grid.tile(),
+            Some(Bounds {
+                min: (0, 0),
+                max: (1, 2)
+            })
+        );
+
+        assert_eq!(grid.get_tiled((2, 3)), Some(&'#'));
+        assert_eq!(grid.get_tiled((-1, -2)), Some(&'#'));
+        assert_eq!(grid.get_tiled((-2, -3)), Some(&'#'));
+        assert_eq!(grid.get_tiled((-2, -2)), None);
+        assert_eq!(grid.get((2, 3)), None);
+    }
+
+    #[test]
+    fn renders_the_occupied_region() {
+        let grid: SparseGrid<char> = neighbours((-5, 10)).map(|p| (p, '#')).collect();
+
+        assert_eq!(grid.to_string(), ".#.\n#.#\n.#.\n");
+        assert_eq!(
+            grid.render(|cell| if cell.is_some() { 'X' } else { ' ' }),
+            " X \nX X\n X \n"
+        );
+    }
+}
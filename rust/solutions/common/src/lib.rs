@@ -0,0 +1,39 @@
+pub mod budget;
+pub mod graph;
+pub mod grid;
+pub mod memo;
+pub mod parse;
+pub mod plugin;
+pub mod registry;
+
+use std::fmt;
+
+/// Error returned by solvers that report bad input instead of panicking
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The input didn't match the puzzle format
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The input parsed, but doesn't describe a puzzle with an answer
+    Invalid(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "parse error at line {line}, column {column}: {message}"),
+            Error::Invalid(message) => write!(f, "invalid input: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T> = std::result::Result<T, Error>;
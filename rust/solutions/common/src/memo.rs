@@ -0,0 +1,141 @@
+//! Memoisation for recursive solutions. The cache is an ordinary value passed
+//! down the recursion, rather than a global, so every input starts afresh and
+//! nothing leaks between the runner's parts.
+//!
+//! ```
+//! use common::memo::Memo;
+//!
+//! fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+//!     memo.get(n, |memo, &n| match n {
+//!         0 | 1 => n,
+//!         _ => fibonacci(memo, n - 1) + fibonacci(memo, n - 2),
+//!     })
+//! }
+//!
+//! let mut memo = Memo::new("fibonacci");
+//! assert_eq!(fibonacci(&mut memo, 90), 2880067194370816120);
+//! assert_eq!(memo.stats().misses, 91);
+//! ```
+
+use std::{collections::HashMap, hash::Hash};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+/// Results of a function keyed by its arguments, usually a tuple of them.
+/// The hit and miss counts go to the trace output when the memo is cleared
+/// or dropped.
+pub struct Memo<K, V> {
+    #[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+    name: &'static str,
+    cache: HashMap<K, V>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Memo<K, V> {
+    /// `name` labels the memo's counts in the trace output
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            cache: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// The cached value for `key`, or else the value of `compute`, which is
+    /// given the memo back to make its recursive calls through
+    pub fn get(&mut self, key: K, compute: impl FnOnce(&mut Self, &K) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+
+        self.misses += 1;
+        let value = compute(self, &key);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.cache.len(),
+        }
+    }
+
+    /// Forgets every result and resets the counts, for reuse on another input
+    pub fn clear(&mut self) {
+        self.report();
+        self.cache.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+impl<K, V> Memo<K, V> {
+    fn report(&self) {
+        #[cfg(feature = "tracing")]
+        if self.hits + self.misses > 0 {
+            tracing::debug!(
+                memo = self.name,
+                hits = self.hits,
+                misses = self.misses,
+                entries = self.cache.len(),
+                "memo stats"
+            );
+        }
+    }
+}
+
+impl<K, V> Drop for Memo<K, V> {
+    fn drop(&mut self) {
+        self.report();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Ways to make `total` from coins of the first `n` kinds
+    fn ways(memo: &mut Memo<(usize, u32), u64>, coins: &[u32], n: usize, total: u32) -> u64 {
+        memo.get((n, total), |memo, _| match (n, total) {
+            (_, 0) => 1,
+            (0, _) => 0,
+            _ if coins[n - 1] > total => ways(memo, coins, n - 1, total),
+            _ => ways(memo, coins, n - 1, total) + ways(memo, coins, n, total - coins[n - 1]),
+        })
+    }
+
+    #[test]
+    fn counts_hits_and_misses() {
+        let coins = [1, 2, 5, 10, 20, 50, 100, 200];
+        let mut memo = Memo::new("coins");
+
+        assert_eq!(ways(&mut memo, &coins, coins.len(), 200), 73682);
+
+        let stats = memo.stats();
+        assert_eq!(stats.misses as usize, stats.entries);
+        assert!(stats.hits > 0);
+    }
+
+    #[test]
+    fn clearing_starts_afresh() {
+        let mut memo = Memo::new("coins");
+        ways(&mut memo, &[1, 2], 2, 10);
+
+        memo.clear();
+        assert_eq!(memo.stats(), Stats::default());
+
+        // The same key gets recomputed from the new input
+        assert_eq!(ways(&mut memo, &[3], 1, 10), 0);
+        assert_eq!(ways(&mut memo, &[3], 1, 9), 1);
+    }
+}
@@ -0,0 +1,108 @@
+use nom::{
+    character::complete::multispace0,
+    error::{Error as NomError, ParseError},
+    sequence::delimited,
+    IResult,
+};
+
+use crate::Error;
+
+// Nom utility wrapper - eat any whitespace around a parsed object
+pub fn ws<'a, F, O, E: ParseError<&'a str>>(
+    inner: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O, E> + 'a,
+{
+    delimited(multispace0, inner, multispace0)
+}
+
+// 1-based line and column of `remaining` within `input`
+fn position(input: &str, remaining: &str) -> (usize, usize) {
+    let offset = input.len() - remaining.len();
+    let consumed = &input[..offset];
+    let line = consumed.matches('\n').count() + 1;
+    let column = consumed.len() - consumed.rfind('\n').map_or(0, |i| i + 1) + 1;
+    (line, column)
+}
+
+fn parse_error(input: &str, remaining: &str, message: String) -> Error {
+    let (line, column) = position(input, remaining);
+    Error::Parse {
+        line,
+        column,
+        message,
+    }
+}
+
+/// Runs a parser over the whole input, allowing only trailing whitespace to be
+/// left over, and turns nom's error into one pointing at the offending line
+pub fn finish<'a, O>(
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+    input: &'a str,
+) -> Result<O, Error> {
+    match parser(input) {
+        Ok((remaining, output)) if remaining.trim().is_empty() => Ok(output),
+        Ok((remaining, _)) => Err(parse_error(
+            input,
+            remaining,
+            "unexpected trailing input".to_string(),
+        )),
+        Err(nom::Err::Error(NomError { input: at, code }))
+        | Err(nom::Err::Failure(NomError { input: at, code })) => {
+            Err(parse_error(input, at, format!("expected {code:?}")))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(parse_error(
+            input,
+            "",
+            "unexpected end of input".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::{
+        bytes::complete::tag,
+        character::complete::{self, newline},
+        multi::separated_list1,
+        sequence::preceded,
+    };
+
+    fn numbers(input: &str) -> IResult<&str, Vec<u32>> {
+        preceded(tag("numbers: "), separated_list1(newline, complete::u32))(input)
+    }
+
+    #[test]
+    fn ws_eats_surrounding_whitespace() {
+        let result: IResult<&str, &str> = ws(tag("|"))("  |  83");
+        assert_eq!(result, Ok(("83", "|")));
+    }
+
+    #[test]
+    fn finish_allows_trailing_whitespace() {
+        assert_eq!(finish(numbers, "numbers: 1\n2\n3\n"), Ok(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn finish_reports_where_parsing_stopped() {
+        assert_eq!(
+            finish(numbers, "numbers: 1\n2\nthree"),
+            Err(Error::Parse {
+                line: 2,
+                column: 2,
+                message: "unexpected trailing input".to_string(),
+            })
+        );
+
+        assert_eq!(
+            finish(numbers, "digits: 1"),
+            Err(Error::Parse {
+                line: 1,
+                column: 1,
+                message: "expected Tag".to_string(),
+            })
+        );
+    }
+}
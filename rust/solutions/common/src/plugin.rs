@@ -0,0 +1,215 @@
+//! The interface between the runner and a day crate built as a `cdylib`, so a
+//! day can be rebuilt and swapped into a running `aoc watch` on its own.
+//!
+//! The library and the runner each have their own copy of std, with its own
+//! allocator and panic machinery, so nothing Rust-shaped crosses between them:
+//! the input goes in as bytes, the answer comes back as a [`RawAnswer`] that
+//! only the library may free, and panics are caught before they get out.
+//!
+//! A day crate exports its descriptor with [`export_plugin!`], which only does
+//! anything with the crate's `plugin` feature on, as the runner links every day
+//! together and each would clash over the same symbol.
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    ptr, slice, str,
+};
+
+use crate::{registry, Error, Result};
+
+/// Bumped whenever [`Descriptor`] or [`RawAnswer`] change shape, so an old
+/// library is turned away rather than misread
+pub const ABI_VERSION: u32 = 1;
+
+/// Name of the exported [`Descriptor`] static
+pub const SYMBOL: &[u8] = b"AOC_PLUGIN\0";
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Solved,
+    /// `line` and `column` say where, and the text is the parser's message
+    ParseError,
+    Invalid,
+    Panicked,
+}
+
+/// An answer or error message owned by the library that made it. Hand it back
+/// to [`Descriptor::free`] once it's read.
+#[repr(C)]
+pub struct RawAnswer {
+    pub status: Status,
+    pub line: usize,
+    pub column: usize,
+    pub text: *mut u8,
+    pub len: usize,
+}
+
+pub type PartFn = unsafe extern "C" fn(input: *const u8, len: usize) -> RawAnswer;
+
+#[repr(C)]
+pub struct Descriptor {
+    pub abi_version: u32,
+    pub day: u8,
+    pub parts: [PartFn; 2],
+    pub free: unsafe extern "C" fn(answer: RawAnswer),
+}
+
+impl Descriptor {
+    /// Describes the solvers registered for `DAY` in this library
+    pub const fn new<const DAY: u8>() -> Self {
+        Self {
+            abi_version: ABI_VERSION,
+            day: DAY,
+            parts: [solve::<DAY, 1>, solve::<DAY, 2>],
+            free,
+        }
+    }
+}
+
+impl RawAnswer {
+    fn new(status: Status, (line, column): (usize, usize), text: String) -> Self {
+        let text = Box::into_raw(text.into_boxed_str().into_boxed_bytes());
+        Self {
+            status,
+            line,
+            column,
+            text: text.cast(),
+            len: text.len(),
+        }
+    }
+
+    /// The answer as a solver would have returned it, or `Err` with the panic
+    /// message if it panicked
+    ///
+    /// # Safety
+    ///
+    /// `text` and `len` must describe valid UTF-8, as they do for any answer
+    /// the library made and hasn't freed yet
+    pub unsafe fn read(&self) -> std::result::Result<Result<String>, String> {
+        let text = str::from_utf8_unchecked(slice::from_raw_parts(self.text, self.len));
+        let text = text.to_string();
+
+        Ok(match self.status {
+            Status::Solved => Ok(text),
+            Status::ParseError => Err(Error::Parse {
+                line: self.line,
+                column: self.column,
+                message: text,
+            }),
+            Status::Invalid => Err(Error::Invalid(text)),
+            Status::Panicked => return Err(text),
+        })
+    }
+}
+
+/// Runs the default solver for one part of `DAY`
+///
+/// # Safety
+///
+/// `input` and `len` must describe valid memory for the length of the call
+pub unsafe extern "C" fn solve<const DAY: u8, const PART: u8>(
+    input: *const u8,
+    len: usize,
+) -> RawAnswer {
+    let input = slice::from_raw_parts(input, len);
+
+    let solved = panic::catch_unwind(AssertUnwindSafe(|| {
+        let input = str::from_utf8(input)
+            .map_err(|err| Error::Invalid(format!("input isn't UTF-8: {err}")))?;
+
+        let solution = registry::solutions()
+            .find(|s| s.day == DAY && s.part == PART && s.name == registry::DEFAULT)
+            .ok_or_else(|| Error::Invalid("no solver is registered for this part".to_string()))?;
+        (solution.solver)(input)
+    }));
+
+    match solved {
+        Ok(Ok(answer)) => RawAnswer::new(Status::Solved, (0, 0), answer),
+        Ok(Err(Error::Parse {
+            line,
+            column,
+            message,
+        })) => RawAnswer::new(Status::ParseError, (line, column), message),
+        Ok(Err(Error::Invalid(message))) => RawAnswer::new(Status::Invalid, (0, 0), message),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            RawAnswer::new(Status::Panicked, (0, 0), message)
+        }
+    }
+}
+
+/// Frees an answer made by [`solve`]
+///
+/// # Safety
+///
+/// `answer` must have come from this library, and not been freed already
+pub unsafe extern "C" fn free(answer: RawAnswer) {
+    drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+        answer.text,
+        answer.len,
+    )));
+}
+
+/// Exports the day's [`Descriptor`] when the crate is built with its `plugin`
+/// feature, for loading with `aoc watch --plugin`
+///
+/// ```ignore
+/// common::export_plugin!(day = 4);
+/// ```
+#[macro_export]
+macro_rules! export_plugin {
+    (day = $day:literal) => {
+        #[cfg(feature = "plugin")]
+        #[no_mangle]
+        pub static AOC_PLUGIN: $crate::plugin::Descriptor =
+            $crate::plugin::Descriptor::new::<$day>();
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::{inventory, Solution};
+
+    inventory::submit! {
+        Solution { day: 99, part: 1, name: registry::DEFAULT, solver: |input| Ok(input.len().to_string()) }
+    }
+
+    inventory::submit! {
+        Solution { day: 99, part: 2, name: registry::DEFAULT, solver: |_| panic!("no part 2") }
+    }
+
+    fn call(part: PartFn, input: &str) -> std::result::Result<Result<String>, String> {
+        let descriptor = Descriptor::new::<99>();
+        unsafe {
+            let answer = part(input.as_ptr(), input.len());
+            let read = answer.read();
+            (descriptor.free)(answer);
+            read
+        }
+    }
+
+    #[test]
+    fn answers_cross_the_boundary() {
+        let descriptor = Descriptor::new::<99>();
+        assert_eq!(descriptor.abi_version, ABI_VERSION);
+
+        assert_eq!(call(descriptor.parts[0], "four"), Ok(Ok("4".to_string())));
+        assert_eq!(call(descriptor.parts[1], ""), Err("no part 2".to_string()));
+    }
+
+    #[test]
+    fn unregistered_days_are_invalid() {
+        let descriptor = Descriptor::new::<98>();
+
+        assert!(matches!(
+            call(descriptor.parts[0], ""),
+            Ok(Err(Error::Invalid(_)))
+        ));
+    }
+}
@@ -0,0 +1,54 @@
+//! Solvers and parsers registered by the day crates with `#[aoc]` and
+//! `#[aoc_parser]`, collected at link time
+
+pub use inventory;
+
+use crate::Result;
+
+/// Name of a part's main solver, as opposed to its alternatives
+pub const DEFAULT: &str = "default";
+
+pub struct Solution {
+    pub day: u8,
+    pub part: u8,
+    pub name: &'static str,
+    pub solver: fn(&str) -> Result<String>,
+}
+
+pub struct Parser {
+    pub day: u8,
+    pub name: &'static str,
+    /// Whether the parser reads the whole input, and where it stopped if not
+    pub parse: fn(&str) -> Result<()>,
+}
+
+inventory::collect!(Solution);
+inventory::collect!(Parser);
+
+/// Every registered solver, in no particular order
+pub fn solutions() -> impl Iterator<Item = &'static Solution> {
+    inventory::iter::<Solution>.into_iter()
+}
+
+/// Every registered parser, in no particular order
+pub fn parsers() -> impl Iterator<Item = &'static Parser> {
+    inventory::iter::<Parser>.into_iter()
+}
+
+/// What solvers may return: most days panic on bad input and return the
+/// answer as it is, while some report a [`crate::Error`]
+pub trait IntoAnswer {
+    fn into_answer(self) -> Result<String>;
+}
+
+impl IntoAnswer for String {
+    fn into_answer(self) -> Result<String> {
+        Ok(self)
+    }
+}
+
+impl IntoAnswer for Result<String> {
+    fn into_answer(self) -> Result<String> {
+        self
+    }
+}
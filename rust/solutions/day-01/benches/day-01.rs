@@ -0,0 +1,49 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_01::{process_part1, process_part2, process_part2_fold_while};
+use std::hint::black_box;
+
+const PIECES: [&str; 16] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "twone", "oneight",
+    "x", "qz", "pr", "7", "abc",
+];
+
+// input.txt isn't checked in, so make up lines of about the same length and
+// mix of words, numerals and noise
+fn input() -> String {
+    let mut seed = 0x2023_u64;
+    let mut next = move || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) as usize
+    };
+
+    (0..1000)
+        .map(|_| {
+            let mut line: String = (0..8).map(|_| PIECES[next() % PIECES.len()]).collect();
+            line.insert(line.len() / 2, char::from(b'1' + (next() % 9) as u8));
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn parts(c: &mut Criterion) {
+    let input = input();
+
+    c.bench_function("day 01 part 1", |b| {
+        b.iter(|| process_part1(black_box(&input)))
+    });
+
+    let mut part2 = c.benchmark_group("day 01 part 2");
+    part2.bench_function("aho-corasick", |b| {
+        b.iter(|| process_part2(black_box(&input)))
+    });
+    part2.bench_function("fold-while", |b| {
+        b.iter(|| process_part2_fold_while(black_box(&input)))
+    });
+    part2.finish();
+}
+
+criterion_group!(benches, parts);
+criterion_main!(benches);
@@ -0,0 +1,83 @@
+use std::fmt;
+
+use crate::{calibrate, Matcher, OverlapMode, Policy};
+
+/// A line worth different amounts depending on how overlapping words count
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ambiguity {
+    /// Counting from 1
+    pub line_no: usize,
+    pub line: String,
+    /// The line's calibration value in each of [`OverlapMode::ALL`], in order
+    pub values: [u32; OverlapMode::ALL.len()],
+}
+
+impl fmt::Display for Ambiguity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {} {}:", self.line_no, self.line)?;
+        for (mode, value) in OverlapMode::ALL.iter().zip(self.values) {
+            write!(f, " {} {value}", mode.name())?;
+        }
+        Ok(())
+    }
+}
+
+/// Every line of the input whose calibration value depends on the
+/// [`OverlapMode`]. A line without digits has none in any mode, so it's never
+/// ambiguous
+pub fn ambiguities(input: &str, matcher: &Matcher) -> Vec<Ambiguity> {
+    let [overlapping, leftmost, longest] = OverlapMode::ALL.map(|mode| {
+        calibrate(input, &matcher.clone().with_overlap(mode), Policy::Skip)
+            .expect("skipping lines without digits can't fail")
+    });
+
+    let lines: Vec<&str> = input.lines().collect();
+
+    // Every mode skips the same lines, so the calibrations line up
+    overlapping
+        .into_iter()
+        .zip(leftmost)
+        .zip(longest)
+        .filter_map(|((overlapping, leftmost), longest)| {
+            let values = [overlapping.value, leftmost.value, longest.value];
+            let ambiguous = values.iter().any(|&value| value != values[0]);
+
+            ambiguous.then(|| Ambiguity {
+                line_no: overlapping.line_no,
+                line: lines[overlapping.line_no - 1].to_string(),
+                values,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_lines_that_depend_on_the_mode() {
+        let input = "eightwothree\nxtwone\n7\nnothing\nuneuf";
+        let ambiguous = ambiguities(input, &Matcher::default());
+
+        assert_eq!(
+            ambiguous,
+            [Ambiguity {
+                line_no: 2,
+                line: "xtwone".to_string(),
+                values: [21, 22, 22],
+            }]
+        );
+        assert_eq!(
+            ambiguous[0].to_string(),
+            "line 2 xtwone: overlapping 21 leftmost 22 longest 22"
+        );
+
+        let french = Matcher::new(&crate::Vocabulary::french());
+        let lines: Vec<usize> = ambiguities(input, &french)
+            .iter()
+            .map(|a| a.line_no)
+            .collect();
+        assert_eq!(lines, [5]);
+    }
+}
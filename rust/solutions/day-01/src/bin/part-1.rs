@@ -1,7 +1,8 @@
 use day_01::process_part1;
-use std::fs;
+use std::{error::Error, fs};
 
-fn main() {
-    let file = fs::read_to_string("./input.txt").unwrap();
-    println!("{}", process_part1(&file));
+fn main() -> Result<(), Box<dyn Error>> {
+    let file = fs::read_to_string("./input.txt")?;
+    println!("{}", process_part1(&file)?);
+    Ok(())
 }
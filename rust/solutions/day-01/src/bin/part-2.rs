@@ -1,7 +1,64 @@
-use day_01::process_part2;
-use std::fs;
+use day_01::{
+    ambiguities, process_part2_numbers, process_part2_with, Matcher, OverlapMode, Policy,
+    Vocabulary,
+};
+use std::{env, error::Error, fs, path::Path};
 
-fn main() {
-    let file = fs::read_to_string("./input.txt").unwrap();
-    println!("{}", process_part2(&file));
+// Takes an optional language, or a file of `word = digit` lines, for the
+// spelled out numbers. `--numbers` reads whole English numbers instead,
+// `--overlap leftmost|longest` changes which overlapping words count, and
+// `--ambiguous` lists the lines that depend on it
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut numbers = false;
+    let mut vocabulary = None;
+    let mut overlap = None;
+    let mut ambiguous = false;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--numbers" => numbers = true,
+            "--ambiguous" => ambiguous = true,
+            "--overlap" => {
+                let name = args.next().unwrap_or_default();
+                let mode = OverlapMode::from_name(&name)
+                    .ok_or_else(|| format!("unknown overlap mode {name:?}"))?;
+                overlap = Some(mode);
+            }
+            name => {
+                vocabulary = Some(match Vocabulary::builtin(name) {
+                    Some(vocabulary) => vocabulary,
+                    None => Vocabulary::from_file(Path::new(name))?,
+                })
+            }
+        }
+    }
+
+    let file = fs::read_to_string("./input.txt")?;
+
+    if numbers {
+        // Whole numbers are always English, and their overlaps have rules of
+        // their own
+        if vocabulary.is_some() || overlap.is_some() || ambiguous {
+            return Err(
+                "--numbers can't be combined with a vocabulary, --overlap or --ambiguous".into(),
+            );
+        }
+        println!("{}", process_part2_numbers(&file, Policy::Error)?);
+        return Ok(());
+    }
+
+    let vocabulary = vocabulary.unwrap_or_default();
+    if ambiguous {
+        for ambiguity in ambiguities(&file, &Matcher::new(&vocabulary)) {
+            println!("{ambiguity}");
+        }
+    }
+
+    let overlap = overlap.unwrap_or_default();
+    println!(
+        "{}",
+        process_part2_with(&file, &vocabulary, overlap, Policy::Error)?
+    );
+    Ok(())
 }
@@ -0,0 +1,159 @@
+use common::Error;
+
+use crate::{Match, Matcher};
+
+/// What to do with a line that has no digits in it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Policy {
+    /// Leave the line out
+    Skip,
+    /// Keep the line, worth nothing
+    Zero,
+    /// Stop with a parse error pointing at the line
+    #[default]
+    Error,
+}
+
+/// A line's calibration value, made of its first and last digits, or of its
+/// first and last [`crate::Number`]s
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calibration<T = Match> {
+    /// Counting from 1
+    pub line_no: usize,
+    /// Only missing from a line with no digits, kept by [`Policy::Zero`]
+    pub first: Option<T>,
+    pub last: Option<T>,
+    pub value: u32,
+}
+
+/// Calibrates every line of the input, with `policy` deciding what becomes of
+/// lines without digits
+pub fn calibrate(
+    input: &str,
+    matcher: &Matcher,
+    policy: Policy,
+) -> common::Result<Vec<Calibration>> {
+    calibrate_with(
+        input,
+        policy,
+        |line, _| Ok(matcher.first_and_last(line)),
+        |first, last| Some(u32::from(first.digit * 10 + last.digit)),
+    )
+}
+
+// Calibrates each line from the first and last things `find` finds in it,
+// given the line and its number, with `value` combining them, or `None` if the
+// value is too big
+pub(crate) fn calibrate_with<T>(
+    input: &str,
+    policy: Policy,
+    find: impl Fn(&str, usize) -> common::Result<Option<(T, T)>>,
+    value: impl Fn(&T, &T) -> Option<u32>,
+) -> common::Result<Vec<Calibration<T>>> {
+    input
+        .lines()
+        .zip(1..)
+        .filter_map(|(line, line_no)| {
+            let found = match find(line, line_no) {
+                Ok(found) => found,
+                Err(err) => return Some(Err(err)),
+            };
+
+            let calibration = match (found, policy) {
+                (Some((first, last)), _) => {
+                    let Some(value) = value(&first, &last) else {
+                        return Some(Err(Error::Invalid(format!(
+                            "the calibration value of line {line_no} is too large"
+                        ))));
+                    };
+                    Calibration {
+                        line_no,
+                        first: Some(first),
+                        last: Some(last),
+                        value,
+                    }
+                }
+                (None, Policy::Skip) => return None,
+                (None, Policy::Zero) => Calibration {
+                    line_no,
+                    first: None,
+                    last: None,
+                    value: 0,
+                },
+                (None, Policy::Error) => {
+                    return Some(Err(Error::Parse {
+                        line: line_no,
+                        column: 1,
+                        message: "expected a line with a digit in it".to_string(),
+                    }))
+                }
+            };
+
+            #[cfg(feature = "tracing")]
+            tracing::trace!(line, value = calibration.value, "calibration value");
+
+            Some(Ok(calibration))
+        })
+        .collect()
+}
+
+/// The sum of the calibration values, which can be too big for a `u32` once
+/// each line is worth up to `u32::MAX`
+pub fn total<T>(calibrations: &[Calibration<T>]) -> u64 {
+    calibrations
+        .iter()
+        .map(|calibration| u64::from(calibration.value))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Spelling;
+
+    #[test]
+    fn records_where_each_digit_came_from() {
+        let calibrations =
+            calibrate("ab7\nxtwone3four\n", &Matcher::default(), Policy::Error).unwrap();
+
+        assert_eq!(calibrations[0].value, 77);
+        assert_eq!(calibrations[0].first, calibrations[0].last);
+
+        let Calibration {
+            line_no,
+            first: Some(first),
+            last: Some(last),
+            value,
+        } = &calibrations[1]
+        else {
+            panic!("line 2 has digits");
+        };
+        assert_eq!((*line_no, *value), (2, 24));
+        assert_eq!((first.spelling, first.span.clone()), (Spelling::Word, 1..4));
+        assert_eq!((last.spelling, last.span.clone()), (Spelling::Word, 7..11));
+    }
+
+    #[test]
+    fn lines_without_digits_follow_the_policy() {
+        let input = "1abc2\nnothing\n7";
+        let matcher = Matcher::numerals();
+
+        let skipped = calibrate(input, &matcher, Policy::Skip).unwrap();
+        assert_eq!(
+            skipped.iter().map(|c| c.line_no).collect::<Vec<_>>(),
+            [1, 3]
+        );
+
+        let zeroed = calibrate(input, &matcher, Policy::Zero).unwrap();
+        assert_eq!(
+            zeroed.iter().map(|c| c.value).collect::<Vec<_>>(),
+            [12, 0, 77]
+        );
+        assert_eq!(total(&zeroed), total(&skipped));
+
+        assert!(matches!(
+            calibrate(input, &matcher, Policy::Error),
+            Err(Error::Parse { line: 2, .. })
+        ));
+    }
+}
@@ -1,68 +1,67 @@
+use aoc_macros::{aoc, aoc_parser};
+use common::Error;
 use itertools::FoldWhile::{Continue, Done};
 use itertools::Itertools;
-use std::collections::HashMap;
+use nom::{
+    bytes::complete::take_while1, character::complete::line_ending, multi::separated_list1, IResult,
+};
+
+mod ambiguity;
+mod calibration;
+mod matcher;
+mod numbers;
+mod vocabulary;
+
+pub use ambiguity::{ambiguities, Ambiguity};
+pub use calibration::{calibrate, total, Calibration, Policy};
+pub use matcher::{Match, Matcher, OverlapMode, Spelling};
+pub use numbers::{
+    calibrate_numbers, first_and_last_numbers, number_value, numbers, Number, TooLarge,
+};
+pub use vocabulary::{Vocabulary, LANGUAGES};
+
+common::export_plugin!(day = 1);
+
+// Lines of lower case letters and numerals, the digits spelled out or not
+#[aoc_parser(day = 1)]
+pub fn calibration_document(input: &str) -> IResult<&str, Vec<&str>> {
+    let line = take_while1(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit());
+    let (input, lines) = separated_list1(line_ending, line)(input)?;
+
+    Ok((input, lines))
+}
 
-pub fn process_part1(input: &str) -> String {
-    input
-        .lines()
-        .map(|line| {
-            // Algorithm needs to handle lines with only one number in them
-            // Therefore can't consume the first number when we find/take it
-            // -- need to use peekable or generate a new Chars iterable for both
-            // first and last number?
-            let first_num = line
-                .chars()
-                .find(|char| char.to_digit(10).is_some())
-                .unwrap();
-
-            let last_num = line
-                .chars()
-                .rfind(|char| char.to_digit(10).is_some())
-                .unwrap();
+#[aoc(day = 1, part = 1)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(lines = input.lines().count()))
+)]
+pub fn process_part1(input: &str) -> common::Result<String> {
+    let calibrations = calibrate(input, &Matcher::numerals(), Policy::Error)?;
 
-            String::from_iter([first_num, last_num])
-                .parse::<u32>()
-                .unwrap()
-        })
-        .sum::<u32>()
-        .to_string()
+    Ok(total(&calibrations).to_string())
 }
 
-pub fn extract_word_as_number(word: &str) -> Option<&str> {
-    let number_map = HashMap::from([
-        ("one", "1"),
-        ("two", "2"),
-        ("three", "3"),
-        ("four", "4"),
-        ("five", "5"),
-        ("six", "6"),
-        ("seven", "7"),
-        ("eight", "8"),
-        ("nine", "9"),
-    ]);
-
-    for key in number_map.keys() {
-        if word.contains(key) {
-            return Some(number_map.get(key).unwrap());
-        };
-    }
-
-    None
+/// The digit spelled by a word in `word`, if there is one. When several fit,
+/// the longest wins.
+pub fn extract_word_as_number(word: &str, vocabulary: &Vocabulary) -> Option<u8> {
+    vocabulary
+        .words()
+        .find(|(number_word, _)| word.contains(number_word))
+        .map(|(_, digit)| digit)
 }
 
-pub fn get_first_number(line: &str) -> String {
+pub fn get_first_number(line: &str, vocabulary: &Vocabulary) -> String {
     line.chars()
         .fold_while("".to_string(), |mut curr, char| {
-            if char.to_digit(10).is_some() {
+            if char.is_ascii_digit() {
                 return Done(char.to_string());
             }
 
             curr.push(char);
-            let extracted_number = extract_word_as_number(&curr);
-            if extracted_number.is_some() {
-                Done(extracted_number.unwrap().to_string())
-            } else {
-                Continue(curr)
+            match extract_word_as_number(&curr, vocabulary) {
+                Some(extracted_number) => Done(extracted_number.to_string()),
+                None => Continue(curr),
             }
         })
         .into_inner()
@@ -71,70 +70,82 @@ pub fn get_first_number(line: &str) -> String {
 // Getting the last number for words we can't just iterate backwards
 // as the spelling of words will be reversed. There's definitely
 // a more elegant way to handle this!
-pub fn get_last_number(line: &str) -> String {
+pub fn get_last_number(line: &str, vocabulary: &Vocabulary) -> String {
     line.chars()
         .rev()
         .fold_while("".to_string(), |mut curr, char| {
-            if char.to_digit(10).is_some() {
+            if char.is_ascii_digit() {
                 return Done(char.to_string());
             }
 
             curr.insert(0, char);
-            let extracted_number = extract_word_as_number(&curr);
-            if extracted_number.is_some() {
-                Done(extracted_number.unwrap().to_string())
-            } else {
-                Continue(curr)
+            match extract_word_as_number(&curr, vocabulary) {
+                Some(extracted_number) => Done(extracted_number.to_string()),
+                None => Continue(curr),
             }
         })
         .into_inner()
 }
 
-pub fn process_part2(input: &str) -> String {
+#[aoc(day = 1, part = 2)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(lines = input.lines().count()))
+)]
+pub fn process_part2(input: &str) -> common::Result<String> {
+    process_part2_with(
+        input,
+        &Vocabulary::english(),
+        OverlapMode::Overlapping,
+        Policy::Error,
+    )
+}
+
+/// Part 2 with the numbers spelled in another language, a choice of which
+/// overlapping words count, and of what to do about lines without digits
+pub fn process_part2_with(
+    input: &str,
+    vocabulary: &Vocabulary,
+    overlap: OverlapMode,
+    policy: Policy,
+) -> common::Result<String> {
+    let matcher = Matcher::new(vocabulary).with_overlap(overlap);
+    let calibrations = calibrate(input, &matcher, policy)?;
+
+    Ok(total(&calibrations).to_string())
+}
+
+/// Part 2 reading whole numbers like `forty-two` instead of single digits. Its
+/// answers differ from part 2's, so it isn't registered as an alternative
+pub fn process_part2_numbers(input: &str, policy: Policy) -> common::Result<String> {
+    let calibrations = calibrate_numbers(input, policy)?;
+
+    Ok(total(&calibrations).to_string())
+}
+
+/// Part 2 as first written, scanning each line a character at a time from
+/// either end, kept to check and benchmark the matcher against
+#[aoc(day = 1, part = 2, name = "fold-while")]
+pub fn process_part2_fold_while(input: &str) -> common::Result<String> {
+    let vocabulary = Vocabulary::english();
+
     input
         .lines()
-        .map(|line| {
-            let first_num = get_first_number(line);
+        .zip(1..)
+        .map(|(line, line_no)| {
+            let first_num = get_first_number(line, &vocabulary);
 
-            let last_num = get_last_number(line);
+            let last_num = get_last_number(line, &vocabulary);
 
             String::from_iter([first_num, last_num])
-                .parse::<u32>()
-                .unwrap()
+                .parse::<u64>()
+                .map_err(|_| Error::Parse {
+                    line: line_no,
+                    column: 1,
+                    message: "expected a line with a digit in it".to_string(),
+                })
         })
-        .sum::<u32>()
-        .to_string()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn part_1_toy_input() {
-        const PART_1_INPUT: &str = "1abc2
-pqr3stu8vwx
-a1b2c3d4e5f
-treb7uchet
-";
-
-        const PART_1_EXPECTED: &str = "142";
-        let result = process_part1(PART_1_INPUT);
-        assert_eq!(result, PART_1_EXPECTED);
-    }
-
-    #[test]
-    fn part_2_toy_input() {
-        const PART_2_INPUT: &str = "two1nine
-eightwothree
-abcone2threexyz
-xtwone3four
-4nineeightseven2
-zoneight234
-7pqrstsixteen";
-
-        const PART_2_EXPECTED: &str = "281";
-        let result = process_part2(PART_2_INPUT);
-        assert_eq!(result, PART_2_EXPECTED);
-    }
+        // Summed as wide as `total`, so both agree on inputs with many lines
+        .sum::<common::Result<u64>>()
+        .map(|sum| sum.to_string())
 }
@@ -0,0 +1,242 @@
+use std::ops::Range;
+
+use aho_corasick::{AhoCorasick, MatchKind};
+
+use crate::Vocabulary;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spelling {
+    Numeral,
+    Word,
+}
+
+/// A digit found in a line, as a numeral or spelled out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    pub digit: u8,
+    pub spelling: Spelling,
+    /// Byte offsets of the numeral or word within the line
+    pub span: Range<usize>,
+}
+
+/// Which matches count when they overlap, as in `twone` or French `une`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum OverlapMode {
+    /// Every match counts, so `twone` has both a two and a one
+    #[default]
+    Overlapping,
+    /// Reading forwards, the match starting soonest is taken, the shortest of
+    /// those starting together, and the next starts after it, so `twone` is
+    /// only a two, and `une` is `un`
+    Leftmost,
+    /// Reading forwards, the longest match starting soonest is taken and the
+    /// next starts after it, so `twone` is only a two, and `une` is `une`
+    Longest,
+}
+
+impl OverlapMode {
+    pub const ALL: [Self; 3] = [Self::Overlapping, Self::Leftmost, Self::Longest];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Overlapping => "overlapping",
+            Self::Leftmost => "leftmost",
+            Self::Longest => "longest",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+}
+
+const NUMERALS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+/// Finds the numerals and number words in a line in one pass over its bytes,
+/// by default overlapping ones included, so `twone` has both a two and a one
+#[derive(Clone)]
+pub struct Matcher {
+    automaton: AhoCorasick,
+    // The same patterns, finding the leftmost match, and preferring the
+    // shortest or the longest of those starting together
+    leftmost: AhoCorasick,
+    longest: AhoCorasick,
+    // The patterns in `leftmost` are shortest first, so its ids are positions
+    // in here
+    shortest_first: Vec<usize>,
+    overlap: OverlapMode,
+    // The digit each pattern stands for, by pattern id
+    digits: Vec<u8>,
+}
+
+impl Matcher {
+    pub fn new(vocabulary: &Vocabulary) -> Self {
+        let (patterns, digits): (Vec<&str>, Vec<u8>) = NUMERALS
+            .into_iter()
+            .zip(0..)
+            .chain(vocabulary.words())
+            .unzip();
+
+        // Leftmost-first prefers earlier patterns, and the sort is stable, so
+        // the numerals stay at the front
+        let mut shortest_first: Vec<usize> = (0..patterns.len()).collect();
+        shortest_first.sort_by_key(|&pattern| patterns[pattern].len());
+
+        let leftmost = AhoCorasick::builder()
+            .match_kind(MatchKind::LeftmostFirst)
+            .build(shortest_first.iter().map(|&pattern| patterns[pattern]));
+        let longest = AhoCorasick::builder()
+            .match_kind(MatchKind::LeftmostLongest)
+            .build(&patterns);
+
+        Self {
+            automaton: AhoCorasick::new(&patterns).expect("digit patterns should compile"),
+            leftmost: leftmost.expect("digit patterns should compile"),
+            longest: longest.expect("digit patterns should compile"),
+            shortest_first,
+            overlap: OverlapMode::default(),
+            digits,
+        }
+    }
+
+    /// Counts overlapping matches according to `overlap` instead
+    pub fn with_overlap(self, overlap: OverlapMode) -> Self {
+        Self { overlap, ..self }
+    }
+
+    pub fn overlap(&self) -> OverlapMode {
+        self.overlap
+    }
+
+    /// Only numerals, no words
+    pub fn numerals() -> Self {
+        Self::new(&Vocabulary::new([]).expect("no words is a valid vocabulary"))
+    }
+
+    /// Every match in the line that counts under the matcher's [`OverlapMode`],
+    /// in the order they end
+    pub fn matches<'a>(&'a self, line: &'a str) -> impl Iterator<Item = Match> + 'a {
+        let found: Box<dyn Iterator<Item = aho_corasick::Match>> = match self.overlap {
+            OverlapMode::Overlapping => Box::new(self.automaton.find_overlapping_iter(line)),
+            OverlapMode::Leftmost => Box::new(self.leftmost.find_iter(line)),
+            OverlapMode::Longest => Box::new(self.longest.find_iter(line)),
+        };
+
+        found.map(|found| {
+            let pattern = match self.overlap {
+                OverlapMode::Leftmost => self.shortest_first[found.pattern().as_usize()],
+                _ => found.pattern().as_usize(),
+            };
+            Match {
+                digit: self.digits[pattern],
+                // The numerals come first
+                spelling: if pattern < NUMERALS.len() {
+                    Spelling::Numeral
+                } else {
+                    Spelling::Word
+                },
+                span: found.range(),
+            }
+        })
+    }
+
+    /// The first and last digits of a line, read the same way as
+    /// [`crate::get_first_number`] and [`crate::get_last_number`]: the first
+    /// is the match that ends soonest, and the last the one that starts
+    /// latest, with the longer of two matches winning a tie. Other
+    /// [`OverlapMode`]s have no ties, so these are just the first and last
+    /// matches
+    pub fn first_and_last(&self, line: &str) -> Option<(Match, Match)> {
+        self.matches(line).fold(None, |found, next| match found {
+            None => Some((next.clone(), next)),
+            Some((mut first, mut last)) => {
+                if next.span.end == first.span.end && next.span.len() > first.span.len() {
+                    first = next.clone();
+                }
+                if (next.span.start, next.span.len()) > (last.span.start, last.span.len()) {
+                    last = next;
+                }
+                Some((first, last))
+            }
+        })
+    }
+}
+
+impl Default for Matcher {
+    fn default() -> Self {
+        Self::new(&Vocabulary::english())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_overlapping_words_in_one_pass() {
+        let matcher = Matcher::default();
+        let digits: Vec<u8> = matcher.matches("xtwone3four").map(|m| m.digit).collect();
+
+        assert_eq!(digits, [2, 1, 3, 4]);
+    }
+
+    #[test]
+    fn agrees_with_scanning_from_either_end() {
+        let vocabulary = Vocabulary::parse("sept = 7\nseptime = 0\nun = 1\nune = 1\n").unwrap();
+        let matcher = Matcher::new(&vocabulary);
+
+        for line in [
+            "xseptimex",
+            "septimesept",
+            "une9",
+            "4nineeightseven2",
+            "zoneight234",
+        ] {
+            let (first, last) = matcher.first_and_last(line).unwrap();
+            assert_eq!(
+                (first.digit.to_string(), last.digit.to_string()),
+                (
+                    crate::get_first_number(line, &vocabulary),
+                    crate::get_last_number(line, &vocabulary)
+                ),
+                "{line}"
+            );
+        }
+
+        let (first, last) = Matcher::default().first_and_last("eightwothree").unwrap();
+        assert_eq!((first.span, last.span), (0..5, 7..12));
+        assert_eq!(Matcher::numerals().first_and_last("abc"), None);
+    }
+
+    #[test]
+    fn overlap_modes_keep_different_matches() {
+        let vocabulary = Vocabulary::parse("un = 1\nune = 1\ndeux = 2\nneuf = 9\n").unwrap();
+        let matcher = Matcher::new(&vocabulary);
+
+        let words = |mode| -> Vec<(usize, usize)> {
+            let matcher = matcher.clone().with_overlap(mode);
+            matcher
+                .matches("uneuf")
+                .map(|m| (m.span.start, m.span.end))
+                .collect()
+        };
+        assert_eq!(words(OverlapMode::Overlapping), [(0, 2), (0, 3), (1, 5)]);
+        assert_eq!(words(OverlapMode::Leftmost), [(0, 2)]);
+        assert_eq!(words(OverlapMode::Longest), [(0, 3)]);
+
+        let twone = |mode| {
+            let matcher = Matcher::default().with_overlap(mode);
+            let (first, last) = matcher.first_and_last("twone").unwrap();
+            (first.digit, last.digit)
+        };
+        assert_eq!(twone(OverlapMode::Overlapping), (2, 1));
+        assert_eq!(twone(OverlapMode::Leftmost), (2, 2));
+        assert_eq!(twone(OverlapMode::Longest), (2, 2));
+
+        // A word inside another that starts sooner doesn't count
+        let vocabulary = Vocabulary::parse("sevenx = 7\nen = 0\n").unwrap();
+        let matcher = Matcher::new(&vocabulary).with_overlap(OverlapMode::Leftmost);
+        let digits: Vec<u8> = matcher.matches("sevenx4").map(|m| m.digit).collect();
+        assert_eq!(digits, [7, 4]);
+    }
+}
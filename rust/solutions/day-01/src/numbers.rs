@@ -0,0 +1,258 @@
+//! Whole numbers rather than digits: runs of numerals like `42`, and English
+//! numbers up to `nine hundred ninety-nine`, with their words run together or
+//! separated by a hyphen or space (`fortytwo`, `forty-two`, `forty two`).
+//!
+//! Overlaps are settled by length: wherever a number starts, the longest one
+//! starting there counts, so `eighteen` is 18 and never 8, and `seventytwo` is
+//! 72 rather than 70 or 7. The first number in a line is the one that starts
+//! soonest, and the last the one that finishes latest, longest again winning a
+//! tie, so `fortytwo` at the end of a line is 42 rather than 2. Numbers may
+//! still overlap each other as digits do, so `twone` is 2 and then 1.
+
+use std::ops::Range;
+
+use common::Error;
+
+use crate::{
+    calibration::{calibrate_with, Calibration, Policy},
+    Spelling,
+};
+
+/// A whole number found in a line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Number {
+    pub value: u32,
+    pub spelling: Spelling,
+    /// Byte offsets of the number within the line
+    pub span: Range<usize>,
+}
+
+const UNITS: [(&str, u32); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+const TEENS: [(&str, u32); 10] = [
+    ("ten", 10),
+    ("eleven", 11),
+    ("twelve", 12),
+    ("thirteen", 13),
+    ("fourteen", 14),
+    ("fifteen", 15),
+    ("sixteen", 16),
+    ("seventeen", 17),
+    ("eighteen", 18),
+    ("nineteen", 19),
+];
+
+const TENS: [(&str, u32); 8] = [
+    ("twenty", 20),
+    ("thirty", 30),
+    ("forty", 40),
+    ("fifty", 50),
+    ("sixty", 60),
+    ("seventy", 70),
+    ("eighty", 80),
+    ("ninety", 90),
+];
+
+// A value and how many bytes it took
+type Parsed = Option<(u32, usize)>;
+
+fn longest(a: Parsed, b: Parsed) -> Parsed {
+    match (a, b) {
+        (Some(a), Some(b)) if b.1 > a.1 => Some(b),
+        (None, b) => b,
+        (a, _) => a,
+    }
+}
+
+fn word(text: &str, words: &[(&str, u32)]) -> Parsed {
+    words
+        .iter()
+        .filter(|(word, _)| text.starts_with(word))
+        .map(|&(word, value)| (value, word.len()))
+        .max_by_key(|&(_, len)| len)
+}
+
+// `next` straight after, or after a hyphen or space. A separator with nothing
+// after it isn't part of the number.
+fn then(text: &str, next: impl Fn(&str) -> Parsed) -> Parsed {
+    ["", "-", " "]
+        .into_iter()
+        .filter_map(|separator| {
+            let rest = text.strip_prefix(separator)?;
+            next(rest).map(|(value, len)| (value, separator.len() + len))
+        })
+        .fold(None, |best, found| longest(best, Some(found)))
+}
+
+// one, twelve, forty, forty-two
+fn below_hundred(text: &str) -> Parsed {
+    let tens = word(text, &TENS).map(|(tens, len)| {
+        let units = then(&text[len..], |rest| word(rest, &UNITS));
+        units.map_or((tens, len), |(units, more)| (tens + units, len + more))
+    });
+
+    longest(longest(word(text, &UNITS), word(text, &TEENS)), tens)
+}
+
+// nine hundred, nine hundred ninety-nine, one hundred and five
+fn hundreds(text: &str) -> Parsed {
+    let (units, len) = word(text, &UNITS)?;
+    let (_, more) = then(&text[len..], |rest| {
+        rest.starts_with("hundred").then_some((0, 7))
+    })?;
+    let len = len + more;
+
+    let rest = then(&text[len..], |rest| {
+        let and = rest
+            .strip_prefix("and")
+            .and_then(|rest| then(rest, below_hundred))
+            .map(|(value, more)| (value, "and".len() + more));
+        longest(below_hundred(rest), and)
+    });
+
+    Some(match rest {
+        Some((value, more)) => (units * 100 + value, len + more),
+        None => (units * 100, len),
+    })
+}
+
+/// A run of numerals too big for a `u32`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TooLarge {
+    /// Byte offsets of the run within the line
+    pub span: Range<usize>,
+}
+
+/// The longest number at the start of `text`, and how many bytes it took
+fn number(text: &str) -> Option<Result<(u32, Spelling, usize), usize>> {
+    let numerals = text.bytes().take_while(u8::is_ascii_digit).count();
+    if numerals > 0 {
+        let number = match text[..numerals].parse() {
+            Ok(value) => Ok((value, Spelling::Numeral, numerals)),
+            Err(_) => Err(numerals),
+        };
+        return Some(number);
+    }
+
+    let (value, len) = longest(below_hundred(text), hundreds(text))?;
+    Some(Ok((value, Spelling::Word, len)))
+}
+
+/// The longest number starting at each point in the line, in order
+pub fn numbers(line: &str) -> impl Iterator<Item = Result<Number, TooLarge>> + '_ {
+    line.char_indices().filter_map(|(start, _)| {
+        Some(match number(&line[start..])? {
+            Ok((value, spelling, len)) => Ok(Number {
+                value,
+                spelling,
+                span: start..start + len,
+            }),
+            Err(len) => Err(TooLarge {
+                span: start..start + len,
+            }),
+        })
+    })
+}
+
+/// The first and last numbers in the line, as set out in the module docs, or
+/// the first run of numerals too big to read
+pub fn first_and_last_numbers(line: &str) -> Result<Option<(Number, Number)>, TooLarge> {
+    numbers(line).try_fold(None, |found: Option<(Number, Number)>, next| {
+        let next = next?;
+        Ok(match found {
+            None => Some((next.clone(), next)),
+            Some((first, last)) => {
+                // Starts come in order, so the first is already settled
+                let later = next.span.end > last.span.end;
+                Some((first, if later { next } else { last }))
+            }
+        })
+    })
+}
+
+/// The first and last numbers written one after the other, so a line holding
+/// only eighteen is worth 1818 just as a line holding only 7 is worth 77
+pub fn number_value(first: &Number, last: &Number) -> Option<u32> {
+    let shift = 10_u32.checked_pow(last.value.checked_ilog10().unwrap_or(0) + 1)?;
+    first.value.checked_mul(shift)?.checked_add(last.value)
+}
+
+/// Calibrates every line by its first and last whole numbers
+pub fn calibrate_numbers(input: &str, policy: Policy) -> common::Result<Vec<Calibration<Number>>> {
+    let find = |line: &str, line_no| {
+        first_and_last_numbers(line).map_err(|too_large| Error::Parse {
+            line: line_no,
+            column: too_large.span.start + 1,
+            message: format!("{} is too large", &line[too_large.span]),
+        })
+    };
+
+    calibrate_with(input, policy, find, number_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(line: &str) -> Option<(u32, u32)> {
+        let (first, last) = first_and_last_numbers(line).unwrap()?;
+        Some((first.value, last.value))
+    }
+
+    #[test]
+    fn reads_compound_numbers() {
+        assert_eq!(values("ninehundredninetynine"), Some((999, 999)));
+        assert_eq!(values("xnine hundred ninety-nine!"), Some((999, 999)));
+        assert_eq!(values("onehundredandfive"), Some((105, 105)));
+        assert_eq!(values("twelve3forty-two"), Some((12, 42)));
+        assert_eq!(values("17abc2023"), Some((17, 2023)));
+        assert_eq!(values("hundred"), None);
+    }
+
+    #[test]
+    fn longest_number_wins_an_overlap() {
+        assert_eq!(values("eighteen"), Some((18, 18)));
+        assert_eq!(values("eightx"), Some((8, 8)));
+        assert_eq!(values("seventytwone"), Some((72, 1)));
+        assert_eq!(values("twone"), Some((2, 1)));
+        // A separator with nothing after it is left alone
+        assert_eq!(values("forty-"), Some((40, 40)));
+    }
+
+    #[test]
+    fn values_join_the_numbers() {
+        let calibrations =
+            calibrate_numbers("eighteen\nfive2twenty\nnothing", Policy::Skip).unwrap();
+
+        let values: Vec<u32> = calibrations.iter().map(|c| c.value).collect();
+        assert_eq!(values, [1818, 520]);
+    }
+
+    #[test]
+    fn numbers_too_large_are_errors() {
+        let input = "40000\n40000\nx99999999999";
+
+        let calibrations = calibrate_numbers(input, Policy::Error);
+        assert!(matches!(
+            calibrations,
+            Err(Error::Parse {
+                line: 3,
+                column: 2,
+                ..
+            })
+        ));
+
+        let calibrations = calibrate_numbers("40000\n40000", Policy::Error).unwrap();
+        assert_eq!(crate::total(&calibrations), 2 * 4_000_040_000);
+    }
+}
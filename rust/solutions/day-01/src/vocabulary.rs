@@ -0,0 +1,248 @@
+use std::{fs, path::Path};
+
+use common::Error;
+
+/// The words that spell out each digit, in one language or another. Words may
+/// share a prefix or contain each other: the first number is the first word
+/// finished reading forwards and the last is the first finished reading
+/// backwards, and if several finish together the longest wins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vocabulary {
+    // Longest first, so the first word that fits is the longest
+    words: Vec<(String, u8)>,
+}
+
+const ENGLISH: [(&str, u8); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+// "un" and "une" are both one, depending on the noun
+const FRENCH: [(&str, u8); 10] = [
+    ("un", 1),
+    ("une", 1),
+    ("deux", 2),
+    ("trois", 3),
+    ("quatre", 4),
+    ("cinq", 5),
+    ("six", 6),
+    ("sept", 7),
+    ("huit", 8),
+    ("neuf", 9),
+];
+
+const GERMAN: [(&str, u8); 9] = [
+    ("eins", 1),
+    ("zwei", 2),
+    ("drei", 3),
+    ("vier", 4),
+    ("fünf", 5),
+    ("sechs", 6),
+    ("sieben", 7),
+    ("acht", 8),
+    ("neun", 9),
+];
+
+const SPANISH: [(&str, u8); 9] = [
+    ("uno", 1),
+    ("dos", 2),
+    ("tres", 3),
+    ("cuatro", 4),
+    ("cinco", 5),
+    ("seis", 6),
+    ("siete", 7),
+    ("ocho", 8),
+    ("nueve", 9),
+];
+
+/// Names accepted by [`Vocabulary::builtin`]
+pub const LANGUAGES: [&str; 4] = ["english", "french", "german", "spanish"];
+
+impl Vocabulary {
+    /// A vocabulary of `(word, digit)` pairs. Words can't be empty and digits
+    /// go up to 9.
+    pub fn new<'a>(words: impl IntoIterator<Item = (&'a str, u8)>) -> common::Result<Self> {
+        let mut words: Vec<(String, u8)> = words
+            .into_iter()
+            .map(|(word, digit)| match (word.is_empty(), digit) {
+                (true, _) => Err(Error::Invalid("number words can't be empty".to_string())),
+                (false, 0..=9) => Ok((word.to_string(), digit)),
+                (false, _) => Err(Error::Invalid(format!(
+                    "{word} should spell a single digit, not {digit}"
+                ))),
+            })
+            .collect::<common::Result<_>>()?;
+
+        words.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then(a.cmp(b)));
+        if let Some(pair) = words
+            .windows(2)
+            .find(|pair| pair[0].0 == pair[1].0 && pair[0].1 != pair[1].1)
+        {
+            return Err(Error::Invalid(format!(
+                "{} can't spell both {} and {}",
+                pair[0].0, pair[0].1, pair[1].1
+            )));
+        }
+        words.dedup();
+
+        Ok(Self { words })
+    }
+
+    pub fn english() -> Self {
+        Self::new(ENGLISH).expect("built in words are valid")
+    }
+
+    pub fn french() -> Self {
+        Self::new(FRENCH).expect("built in words are valid")
+    }
+
+    pub fn german() -> Self {
+        Self::new(GERMAN).expect("built in words are valid")
+    }
+
+    pub fn spanish() -> Self {
+        Self::new(SPANISH).expect("built in words are valid")
+    }
+
+    /// One of the [`LANGUAGES`] vocabularies by name
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "english" => Some(Self::english()),
+            "french" => Some(Self::french()),
+            "german" => Some(Self::german()),
+            "spanish" => Some(Self::spanish()),
+            _ => None,
+        }
+    }
+
+    /// Reads a vocabulary written one `word = digit` per line. Blank lines and
+    /// lines starting with `#` are skipped.
+    ///
+    /// ```text
+    /// # Dutch
+    /// een = 1
+    /// twee = 2
+    /// ```
+    pub fn parse(text: &str) -> common::Result<Self> {
+        let mut words = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: &str| Error::Parse {
+                line: index + 1,
+                column: line.len() - line.trim_start().len() + 1,
+                message: message.to_string(),
+            };
+            let (word, digit) = trimmed
+                .split_once('=')
+                .ok_or_else(|| error("expected `word = digit`"))?;
+            let digit = digit
+                .trim()
+                .parse::<u8>()
+                .map_err(|_| error("expected a digit after `=`"))?;
+
+            words.push((word.trim(), digit));
+        }
+
+        Self::new(words)
+    }
+
+    /// Reads a vocabulary file in the format [`Vocabulary::parse`] takes
+    pub fn from_file(path: &Path) -> common::Result<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| Error::Invalid(format!("could not read {}: {err}", path.display())))?;
+        Self::parse(&text)
+    }
+
+    /// Every word and its digit, longest word first
+    pub fn words(&self) -> impl Iterator<Item = (&str, u8)> {
+        self.words
+            .iter()
+            .map(|(word, digit)| (word.as_str(), *digit))
+    }
+}
+
+impl Default for Vocabulary {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_first_number, get_last_number, process_part2_with, OverlapMode, Policy};
+
+    #[test]
+    fn longest_shared_prefix_wins() {
+        let vocabulary = Vocabulary::parse("sept = 7\nseptime = 0\n").unwrap();
+
+        // Reading forwards, sept is done before septime is
+        assert_eq!(get_first_number("xseptimex", &vocabulary), "7");
+        assert_eq!(get_last_number("xseptimex", &vocabulary), "0");
+        assert_eq!(get_last_number("septimesept", &vocabulary), "7");
+    }
+
+    #[test]
+    fn other_languages() {
+        assert_eq!(
+            process_part2_with(
+                "deuxtroisune\n",
+                &Vocabulary::french(),
+                OverlapMode::Overlapping,
+                Policy::Error
+            ),
+            Ok("21".to_string())
+        );
+        assert_eq!(
+            process_part2_with(
+                "zweiundvierzig\n",
+                &Vocabulary::german(),
+                OverlapMode::Overlapping,
+                Policy::Error
+            ),
+            Ok("24".to_string())
+        );
+        assert_eq!(
+            process_part2_with(
+                "x4nueveocho\n",
+                &Vocabulary::spanish(),
+                OverlapMode::Overlapping,
+                Policy::Error
+            ),
+            Ok("48".to_string())
+        );
+        assert_eq!(Vocabulary::builtin("Spanish"), Some(Vocabulary::spanish()));
+    }
+
+    #[test]
+    fn bad_vocabulary_files() {
+        assert_eq!(
+            Vocabulary::parse("# Dutch\neen = 1\n  twee 2\n"),
+            Err(Error::Parse {
+                line: 3,
+                column: 3,
+                message: "expected `word = digit`".to_string()
+            })
+        );
+        assert!(matches!(
+            Vocabulary::parse("een = 1\neen = 2\n"),
+            Err(Error::Invalid(_))
+        ));
+        assert!(matches!(
+            Vocabulary::parse("tien = 10"),
+            Err(Error::Invalid(_))
+        ));
+    }
+}
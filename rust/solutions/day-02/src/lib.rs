@@ -1,5 +1,45 @@
+use aoc_macros::{aoc, aoc_parser};
 use itertools::Itertools;
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{self, line_ending},
+    multi::separated_list1,
+    sequence::{preceded, separated_pair},
+    IResult,
+};
 
+common::export_plugin!(day = 2);
+
+// A number of cubes of one colour
+pub type Draw<'a> = (u32, &'a str);
+
+// A game's id and the sets of cubes drawn in it
+pub type Game<'a> = (u32, Vec<Vec<Draw<'a>>>);
+
+// Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue
+pub fn game(input: &str) -> IResult<&str, Game<'_>> {
+    let colour = alt((tag("red"), tag("green"), tag("blue")));
+    let draw = separated_pair(complete::u32, tag(" "), colour);
+    let set = separated_list1(tag(", "), draw);
+
+    separated_pair(
+        preceded(tag("Game "), complete::u32),
+        tag(": "),
+        separated_list1(tag("; "), set),
+    )(input)
+}
+
+#[aoc_parser(day = 2)]
+pub fn games(input: &str) -> IResult<&str, Vec<Game<'_>>> {
+    separated_list1(line_ending, game)(input)
+}
+
+#[aoc(day = 2, part = 1)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(games = input.lines().count()))
+)]
 pub fn process_part1(input: &str) -> String {
     input
         .lines()
@@ -25,6 +65,9 @@ pub fn process_part1(input: &str) -> String {
                 })
             });
 
+            #[cfg(feature = "tracing")]
+            tracing::trace!(game = label, is_possible, "checked game");
+
             if !is_possible {
                 None
             } else {
@@ -33,13 +76,18 @@ pub fn process_part1(input: &str) -> String {
                     .parse::<u32>()
                     .expect("Should be a valid integer");
 
-                return Some(id);
+                Some(id)
             }
         })
         .sum::<u32>()
         .to_string()
 }
 
+#[aoc(day = 2, part = 2)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(games = input.lines().count()))
+)]
 pub fn process_part2(input: &str) -> String {
     input
         .lines()
@@ -51,45 +99,23 @@ pub fn process_part2(input: &str) -> String {
             let groups = sets
                 .split("; ")
                 .flat_map(|set| set.split(", "))
-                .filter_map(|draw| {
+                .map(|draw| {
                     let (qty, colour) = draw
-                        .split_once(" ")
+                        .split_once(' ')
                         .expect("Draw description should have a whitespace");
 
-                    Some((qty.parse::<u32>().unwrap(), colour))
+                    (qty.parse::<u32>().unwrap(), colour)
                 })
                 .into_grouping_map_by(|draw| draw.1)
                 .max_by(|_, a, b| a.0.cmp(&b.0));
 
-            return groups.values().map(|(qty, _)| qty).product::<u32>();
+            let power = groups.values().map(|(qty, _)| qty).product::<u32>();
+
+            #[cfg(feature = "tracing")]
+            tracing::trace!(game, power, "minimum cube set");
+
+            power
         })
         .sum::<u32>()
         .to_string()
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
-";
-
-    const PART_1_EXPECTED: &str = "8";
-    const PART_2_EXPECTED: &str = "2286";
-
-    #[test]
-    fn part_1_toy_input() {
-        let result = process_part1(INPUT);
-        assert_eq!(result, PART_1_EXPECTED);
-    }
-
-    #[test]
-    fn part_2_toy_input() {
-        let result = process_part2(INPUT);
-        assert_eq!(result, PART_2_EXPECTED);
-    }
-}
@@ -1,5 +1,22 @@
+use aoc_macros::{aoc, aoc_parser};
 use itertools::Itertools;
-use std::iter;
+use nom::{
+    bytes::complete::take_while1, character::complete::line_ending, combinator::verify,
+    multi::separated_list1, IResult,
+};
+use std::{iter, num::ParseIntError};
+
+common::export_plugin!(day = 3);
+
+// Rows of the same width, of part numbers and symbols on a background of dots
+#[aoc_parser(day = 3)]
+pub fn schematic(input: &str) -> IResult<&str, Vec<&str>> {
+    let row = take_while1(|c: char| c.is_ascii_digit() || c.is_ascii_punctuation());
+
+    verify(separated_list1(line_ending, row), |rows: &[&str]| {
+        rows.iter().all(|row| row.len() == rows[0].len())
+    })(input)
+}
 
 trait SchematicCharacter {
     fn is_symbol(&self) -> bool;
@@ -31,38 +48,52 @@ fn get_possible_numbers_for_line(line: &str) -> Vec<PossibleNumber> {
 
                 if i == line.len() - 1 {
                     possible_numbers.push(PossibleNumber {
-                        start: i - &acc.len(),
-                        end: i - 1,
+                        start: i + 1 - acc.len(),
+                        end: i,
                         string: acc.clone(),
                     });
                 }
-            } else if acc.len() > 0 {
+            } else if !acc.is_empty() {
                 possible_numbers.push(PossibleNumber {
-                    start: i - &acc.len(),
+                    start: i - acc.len(),
                     end: i - 1,
                     string: acc.clone(),
                 });
                 acc.clear();
             }
 
-            return acc;
+            acc
         });
 
-    return possible_numbers;
+    possible_numbers
 }
 
-pub fn process_part1(input: &str) -> String {
-    let len = input.lines().next().unwrap().len();
+/// A number in the schematic, and whether a symbol next to it makes it a part
+/// number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchematicNumber {
+    pub row: usize,
+    /// First and last columns
+    pub start: usize,
+    pub end: usize,
+    pub value: u32,
+    pub is_part_number: bool,
+}
+
+/// Every number in the schematic, row by row, or the first too big for a `u32`
+pub fn schematic_numbers(input: &str) -> Result<Vec<SchematicNumber>, ParseIntError> {
+    let len = input.lines().next().unwrap_or_default().len();
     let empty_line = '.'.to_string().repeat(len);
 
     iter::once(empty_line.as_str())
         .chain(input.lines())
         .chain(iter::once(empty_line.as_str()))
         .tuple_windows::<(&str, &str, &str)>()
-        .flat_map(|(prev_line, current_line, next_line)| {
+        .enumerate()
+        .flat_map(|(row, (prev_line, current_line, next_line))| {
             let possible_numbers = get_possible_numbers_for_line(current_line);
 
-            possible_numbers.into_iter().filter_map(|possible_number| {
+            possible_numbers.into_iter().map(move |possible_number| {
                 let PossibleNumber { start, end, string } = possible_number;
                 let is_part_number = prev_line
                     .chars()
@@ -73,13 +104,32 @@ pub fn process_part1(input: &str) -> String {
                         character.is_symbol() && i >= 1.max(start) - 1 && i <= end + 1
                     });
 
-                if is_part_number {
-                    Some(string.parse::<u32>().unwrap())
-                } else {
-                    None
-                }
+                #[cfg(feature = "tracing")]
+                tracing::trace!(number = %string, start, end, is_part_number, "checked number");
+
+                Ok(SchematicNumber {
+                    row,
+                    start,
+                    end,
+                    value: string.parse()?,
+                    is_part_number,
+                })
             })
         })
+        .collect()
+}
+
+#[aoc(day = 3, part = 1)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(rows = input.lines().count()))
+)]
+pub fn process_part1(input: &str) -> String {
+    schematic_numbers(input)
+        .expect("A valid integer")
+        .into_iter()
+        .filter(|number| number.is_part_number)
+        .map(|number| number.value)
         .sum::<u32>()
         .to_string()
 }
@@ -96,6 +146,9 @@ fn get_gear_product(numbers: Vec<PossibleNumber>, index: usize) -> Option<u32> {
         })
         .collect_vec();
 
+    #[cfg(feature = "tracing")]
+    tracing::trace!(index, ?adjacent_numbers, "checked gear");
+
     if adjacent_numbers.len() == 2 {
         Some(adjacent_numbers.iter().product())
     } else {
@@ -103,6 +156,11 @@ fn get_gear_product(numbers: Vec<PossibleNumber>, index: usize) -> Option<u32> {
     }
 }
 
+#[aoc(day = 3, part = 2)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(rows = input.lines().count()))
+)]
 pub fn process_part2(input: &str) -> String {
     let len = input.lines().next().unwrap().len();
     let empty_line = '.'.to_string().repeat(len);
@@ -119,57 +177,16 @@ pub fn process_part2(input: &str) -> String {
             ]
             .concat();
 
-            return current_line.chars().enumerate().filter_map(move |item| {
+            current_line.chars().enumerate().filter_map(move |item| {
                 let (i, character) = item;
 
                 if character == '*' {
                     return get_gear_product(possible_numbers.clone(), i);
                 }
 
-                return None;
-            });
+                None
+            })
         })
         .sum::<u32>()
         .to_string()
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598..";
-
-    const PART_1_EXPECTED: &str = "4361";
-    const PART_2_EXPECTED: &str = "467835";
-
-    #[test]
-    fn part_1_toy_input() {
-        let result = process_part1(INPUT);
-        assert_eq!(result, PART_1_EXPECTED);
-    }
-
-    #[test]
-    fn part_1_reddit_example() {
-        let result = process_part1(
-            "........
-.24..4.4
-......*.",
-        );
-        assert_eq!(result, "8");
-    }
-
-    #[test]
-    fn part_2_toy_input() {
-        let result = process_part2(INPUT);
-        assert_eq!(result, PART_2_EXPECTED);
-    }
-}
@@ -1,5 +1,5 @@
-use std::iter;
-
+use aoc_macros::{aoc, aoc_parser};
+use common::memo::Memo;
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
@@ -10,6 +10,8 @@ use nom::{
     IResult,
 };
 
+common::export_plugin!(day = 4);
+
 #[derive(Debug)]
 struct Card {
     left: Vec<u32>,
@@ -17,11 +19,9 @@ struct Card {
 }
 
 // Nom utility wrapper - eat any whitespace around a parsed object
-fn ws<'a, F: 'a, O, E: ParseError<&'a str>>(
-    inner: F,
-) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+fn ws<'a, F, O, E: ParseError<&'a str>>(inner: F) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
 where
-    F: Fn(&'a str) -> IResult<&'a str, O, E>,
+    F: Fn(&'a str) -> IResult<&'a str, O, E> + 'a,
 {
     delimited(multispace0, inner, multispace0)
 }
@@ -43,18 +43,33 @@ fn card(input: &str) -> IResult<&str, Card> {
     Ok((input, Card { left, right }))
 }
 
+#[aoc_parser(day = 4)]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
 fn parse_cards(input: &str) -> IResult<&str, Vec<Card>> {
     let (input, cards) = separated_list1(line_ending, card)(input)?;
 
     Ok((input, cards))
 }
 
+#[aoc(day = 4, part = 1)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(cards = tracing::field::Empty))
+)]
 pub fn process_part1(input: &str) -> String {
     let (_, cards) = parse_cards(input).expect("well formed input");
+
+    #[cfg(feature = "tracing")]
+    tracing::Span::current().record("cards", cards.len());
+
     cards
         .iter()
         .map(|Card { left, right }| {
-            let count = right.iter().filter(|num| left.contains(num)).count().into();
+            let count = right.iter().filter(|num| left.contains(num)).count();
+
+            #[cfg(feature = "tracing")]
+            tracing::trace!(count, "winning numbers");
+
             match count {
                 0 => 0,
                 _ => 2u32.pow((count - 1) as u32),
@@ -64,55 +79,40 @@ pub fn process_part1(input: &str) -> String {
         .to_string()
 }
 
+// Cards won by card `i` and everything it goes on to win, itself included
+fn cards_won(memo: &mut Memo<usize, u32>, wins: &[usize], i: usize) -> u32 {
+    memo.get(i, |memo, &i| {
+        let won = (i + 1..=i + wins[i]).take_while(|&j| j < wins.len());
+        1 + won.map(|j| cards_won(memo, wins, j)).sum::<u32>()
+    })
+}
+
+#[aoc(day = 4, part = 2)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(cards = tracing::field::Empty))
+)]
 pub fn process_part2(input: &str) -> String {
     let (_, cards) = parse_cards(input).expect("well formed input");
-    cards
-        .iter()
-        .enumerate()
-        .fold(
-            iter::repeat(1 as u32).take(cards.len()).collect_vec(),
-            |mut acc, (i, card)| {
-                let Card { left, right } = card;
-                let current_count = acc[i];
-                let wins: usize = right.iter().filter(|num| left.contains(num)).count().into();
-
-                for win_offset in 1..=wins {
-                    let won_index = i + win_offset;
-                    if let Some(v) = acc.get(won_index) {
-                        acc[won_index] = v + current_count;
-                    }
-                }
-                return acc;
-            },
-        )
+
+    #[cfg(feature = "tracing")]
+    tracing::Span::current().record("cards", cards.len());
+
+    let wins = cards
         .iter()
+        .map(|Card { left, right }| right.iter().filter(|num| left.contains(num)).count())
+        .collect_vec();
+
+    let mut memo = Memo::new("cards_won");
+    (0..wins.len())
+        .map(|i| {
+            let won = cards_won(&mut memo, &wins, i);
+
+            #[cfg(feature = "tracing")]
+            tracing::trace!(card = i + 1, wins = wins[i], won, "scratched card");
+
+            won
+        })
         .sum::<u32>()
         .to_string()
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-
-    const PART_1_EXPECTED: &str = "13";
-    const PART_2_EXPECTED: &str = "30";
-
-    #[test]
-    fn part_1_toy_input() {
-        let result = process_part1(INPUT);
-        assert_eq!(result, PART_1_EXPECTED);
-    }
-
-    #[test]
-    fn part_2_toy_input() {
-        let result = process_part2(INPUT);
-        assert_eq!(result, PART_2_EXPECTED);
-    }
-}
@@ -1,3 +1,5 @@
+use aoc_macros::{aoc, aoc_parser};
+use common::budget;
 use itertools::Itertools;
 use nom::{
     bytes::complete::{tag, take_until},
@@ -7,7 +9,9 @@ use nom::{
     IResult,
 };
 
-#[derive(Debug)]
+common::export_plugin!(day = 5);
+
+#[derive(Debug, Clone, Copy)]
 struct RangeSpec {
     dest_start: i64,
     source_start: i64,
@@ -43,6 +47,7 @@ fn map_block(input: &str) -> IResult<&str, (&str, Vec<RangeSpec>)> {
     Ok((input, map_block))
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
 fn map_blocks(input: &str) -> IResult<&str, Vec<(&str, Vec<RangeSpec>)>> {
     let (input, map_blocks) = separated_list1(pair(newline, newline), map_block)(input)?;
 
@@ -55,31 +60,61 @@ fn seeds(input: &str) -> IResult<&str, Vec<i64>> {
     Ok((input, seeds))
 }
 
+// The seeds, then the maps from each kind of thing to the next
+type Almanac<'a> = (Vec<i64>, Vec<(&'a str, Vec<RangeSpec>)>);
+
+#[aoc_parser(day = 5)]
+fn almanac(input: &str) -> IResult<&str, Almanac<'_>> {
+    separated_pair(seeds, pair(newline, newline), map_blocks)(input)
+}
+
+#[aoc(day = 5, part = 1)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(seeds = tracing::field::Empty, maps = tracing::field::Empty)
+    )
+)]
 pub fn process_part1(input: &str) -> String {
-    let (_, (seeds, maps)) =
-        separated_pair(seeds, pair(newline, newline), map_blocks)(input).expect("Well formed map");
+    let (_, (seeds, maps)) = almanac(input).expect("Well formed map");
+
+    #[cfg(feature = "tracing")]
+    tracing::Span::current()
+        .record("seeds", seeds.len())
+        .record("maps", maps.len());
 
     let locations = seeds.into_iter().map(|seed| {
-        maps.iter().fold(seed, |current, (_name, ranges)| {
-            for range in ranges {
-                let RangeSpec {
-                    dest_start,
-                    source_start,
-                    length,
-                } = *range;
-
-                if current >= source_start && current < (source_start + length) {
-                    return dest_start + current - source_start;
-                }
-            }
+        let location = seed_location(seed, &maps);
 
-            current
-        })
+        #[cfg(feature = "tracing")]
+        tracing::trace!(seed, location, "mapped seed");
+
+        location
     });
 
     locations.min().unwrap().to_string()
 }
 
+fn seed_location(seed: i64, maps: &[(&str, Vec<RangeSpec>)]) -> i64 {
+    maps.iter().fold(seed, |current, (_name, ranges)| {
+        for range in ranges {
+            let RangeSpec {
+                dest_start,
+                source_start,
+                length,
+            } = *range;
+
+            if current >= source_start && current < (source_start + length) {
+                return dest_start + current - source_start;
+            }
+        }
+
+        current
+    })
+}
+
 fn seed_ranges(input: &str) -> IResult<&str, Vec<(i64, i64)>> {
     let (input, seeds_specs) = preceded(
         tag("seeds: "),
@@ -129,18 +164,35 @@ fn apply_maps_to_ranges(ranges: Vec<(i64, i64)>, map_specs: Vec<RangeSpec>) -> V
 
     result.append(&mut seeds.clone());
 
-    return result.clone();
+    result.clone()
 }
 
+#[aoc(day = 5, part = 2)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(seeds = tracing::field::Empty, maps = tracing::field::Empty)
+    )
+)]
 pub fn process_part2(input: &str) -> String {
     // let seed_ranges = seed_ranges("seeds: 79 14 55 13");
     let (_, (seed_ranges, maps)) =
         separated_pair(seed_ranges, pair(newline, newline), map_blocks)(input)
             .expect("Well formed map");
 
+    #[cfg(feature = "tracing")]
+    tracing::Span::current()
+        .record("seeds", seed_ranges.len())
+        .record("maps", maps.len());
+
     let location_ranges = maps
         .into_iter()
         .fold(seed_ranges, |current, (_name, specs)| {
+            #[cfg(feature = "tracing")]
+            tracing::trace!(map = _name, ranges = current.len(), "applying map");
+
             apply_maps_to_ranges(current.clone(), specs)
         });
 
@@ -152,57 +204,35 @@ pub fn process_part2(input: &str) -> String {
         .to_string()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "seeds: 79 14 55 13
-
-seed-to-soil map:
-50 98 2
-52 50 48
-
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
-
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
-
-water-to-light map:
-88 18 7
-18 25 70
-
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
-
-temperature-to-humidity map:
-0 69 1
-1 0 69
-
-humidity-to-location map:
-60 56 37
-56 93 4";
-
-    const PART_1_EXPECTED: &str = "35";
-    const PART_2_EXPECTED: &str = "46";
+// Maps every seed in every range on its own. Real inputs have billions of seeds,
+// so this is only here to check the range splitting against.
+#[aoc(day = 5, part = 2, name = "per-seed")]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(seeds = tracing::field::Empty))
+)]
+pub fn process_part2_per_seed(input: &str) -> String {
+    let (_, (seed_ranges, maps)) =
+        separated_pair(seed_ranges, pair(newline, newline), map_blocks)(input)
+            .expect("Well formed map");
 
-    #[ignore]
-    #[test]
-    fn part_1_toy_input() {
-        let result = process_part1(INPUT);
-        assert_eq!(result, PART_1_EXPECTED);
-    }
+    #[cfg(feature = "tracing")]
+    tracing::Span::current().record(
+        "seeds",
+        seed_ranges
+            .iter()
+            .map(|(start, end)| end - start + 1)
+            .sum::<i64>(),
+    );
 
-    #[test]
-    fn part_2_toy_input() {
-        let result = process_part2(INPUT);
-        assert_eq!(result, PART_2_EXPECTED);
-    }
+    seed_ranges
+        .into_iter()
+        .flat_map(|(start, end)| start..=end)
+        .map(|seed| {
+            budget::tick();
+            seed_location(seed, &maps)
+        })
+        .min()
+        .unwrap()
+        .to_string()
 }
@@ -1,3 +1,5 @@
+use aoc_macros::{aoc, aoc_parser};
+use common::budget;
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
@@ -7,6 +9,8 @@ use nom::{
     IResult,
 };
 
+common::export_plugin!(day = 6);
+
 pub fn race_times(input: &str) -> IResult<&str, Vec<u32>> {
     let (input, times) = preceded(
         tuple((tag("Time:"), space1)),
@@ -38,22 +42,48 @@ pub fn winning_range_for_race((l, d): (f64, f64)) -> (u64, u64) {
     let root = (l.powi(2) - 4.0 * d).sqrt();
     let min = (l - root) / 2.0;
     let max = (l + root) / 2.0;
-    let result = (min.floor() as u64 + 1, max.ceil() as u64 - 1);
-    return result;
+    (min.floor() as u64 + 1, max.ceil() as u64 - 1)
+}
+
+// Tries every hold time, rather than solving for the ends of the range
+pub fn winning_count_by_loop((l, d): (u64, u64)) -> u64 {
+    (1..l)
+        .filter(|t| {
+            budget::tick();
+            t * (l - t) > d
+        })
+        .count() as u64
 }
 
+#[aoc_parser(day = 6)]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
 pub fn race_descriptions(input: &str) -> IResult<&str, Vec<(u32, u32)>> {
     let (input, (times, distances)) = separated_pair(race_times, newline, race_distances)(input)?;
     let races = times.into_iter().zip(distances).collect_vec();
     Ok((input, races))
 }
 
+#[aoc(day = 6, part = 1)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(races = tracing::field::Empty))
+)]
 pub fn process_part1(input: &str) -> String {
     let (_, races) = race_descriptions(input).expect("Well formed input");
 
+    #[cfg(feature = "tracing")]
+    tracing::Span::current().record("races", races.len());
+
     races
         .into_iter()
-        .map(|(l, d)| winning_range_for_race((l as f64, d as f64)))
+        .map(|(l, d)| {
+            let range = winning_range_for_race((l as f64, d as f64));
+
+            #[cfg(feature = "tracing")]
+            tracing::trace!(time = l, distance = d, ?range, "winning range");
+
+            range
+        })
         .map(|(min, max)| max - min + 1)
         .product::<u64>()
         .to_string()
@@ -80,32 +110,45 @@ pub fn single_race_distance(input: &str) -> IResult<&str, f64> {
     Ok((input, distance))
 }
 
+#[aoc(day = 6, part = 2)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(time = tracing::field::Empty, distance = tracing::field::Empty)
+    )
+)]
 pub fn process_part2(input: &str) -> String {
     let (_, (time, distance)) =
         separated_pair(single_race_time, newline, single_race_distance)(input).unwrap();
+
+    #[cfg(feature = "tracing")]
+    tracing::Span::current()
+        .record("time", time)
+        .record("distance", distance);
+
     let (min, max) = winning_range_for_race((time, distance));
     (max - min + 1).to_string()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "Time:      7  15   30
-Distance:  9  40  200";
+#[aoc(day = 6, part = 1, name = "loop")]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+pub fn process_part1_loop(input: &str) -> String {
+    let (_, races) = race_descriptions(input).expect("Well formed input");
 
-    const PART_1_EXPECTED: &str = "288";
-    const PART_2_EXPECTED: &str = "71503";
+    races
+        .into_iter()
+        .map(|(l, d)| winning_count_by_loop((l as u64, d as u64)))
+        .product::<u64>()
+        .to_string()
+}
 
-    #[test]
-    fn part_1_toy_input() {
-        let result = process_part1(INPUT);
-        assert_eq!(result, PART_1_EXPECTED);
-    }
+#[aoc(day = 6, part = 2, name = "loop")]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+pub fn process_part2_loop(input: &str) -> String {
+    let (_, (time, distance)) =
+        separated_pair(single_race_time, newline, single_race_distance)(input).unwrap();
 
-    #[test]
-    fn part_2_toy_input() {
-        let result = process_part2(INPUT);
-        assert_eq!(result, PART_2_EXPECTED);
-    }
+    winning_count_by_loop((time as u64, distance as u64)).to_string()
 }
@@ -1,6 +1,17 @@
 use std::cmp::Ordering;
 
+use aoc_macros::{aoc, aoc_parser};
 use itertools::Itertools;
+use nom::{
+    bytes::complete::{tag, take},
+    character::complete::{self, line_ending},
+    combinator::verify,
+    multi::separated_list1,
+    sequence::separated_pair,
+    IResult,
+};
+
+common::export_plugin!(day = 7);
 
 pub fn parse_input(input: &str) -> impl Iterator<Item = (&str, &str)> {
     input.lines().map(|line| line.split_once(" ").unwrap())
@@ -8,6 +19,16 @@ pub fn parse_input(input: &str) -> impl Iterator<Item = (&str, &str)> {
 
 const CHAR_ORDER: &str = "AKQJT98765432";
 
+// 32T3K 765
+#[aoc_parser(day = 7)]
+pub fn hands(input: &str) -> IResult<&str, Vec<(&str, u32)>> {
+    let cards = verify(take(5_usize), |cards: &str| {
+        cards.chars().all(|card| CHAR_ORDER.contains(card))
+    });
+
+    separated_list1(line_ending, separated_pair(cards, tag(" "), complete::u32))(input)
+}
+
 #[derive(Debug, PartialOrd, Ord, PartialEq, Eq)]
 pub enum HandType {
     Five,
@@ -65,12 +86,22 @@ pub fn compare_cards(
     }
 }
 
+#[aoc(day = 7, part = 1)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(hands = input.lines().count()))
+)]
 pub fn process_part1(input: &str) -> String {
     parse_input(input)
         .sorted_by(|a, b| compare_cards(a.0, b.0, hand_type_from_cards, CHAR_ORDER))
         .rev()
         .enumerate()
-        .map(|(i, hand)| (i + 1) * hand.1.parse::<usize>().unwrap())
+        .map(|(i, hand)| {
+            #[cfg(feature = "tracing")]
+            tracing::trace!(rank = i + 1, cards = hand.0, bid = hand.1, "ranked hand");
+
+            (i + 1) * hand.1.parse::<usize>().unwrap()
+        })
         .sum::<usize>()
         .to_string()
 }
@@ -107,6 +138,11 @@ pub fn hand_type_from_cards_with_jokers(hand: &str) -> HandType {
     }
 }
 
+#[aoc(day = 7, part = 2)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(hands = input.lines().count()))
+)]
 pub fn process_part2(input: &str) -> String {
     parse_input(input)
         .sorted_by(|a, b| {
@@ -119,33 +155,12 @@ pub fn process_part2(input: &str) -> String {
         })
         .rev()
         .enumerate()
-        .map(|(i, hand)| (i as u64 + 1) * hand.1.parse::<u64>().unwrap())
+        .map(|(i, hand)| {
+            #[cfg(feature = "tracing")]
+            tracing::trace!(rank = i + 1, cards = hand.0, bid = hand.1, "ranked hand");
+
+            (i as u64 + 1) * hand.1.parse::<u64>().unwrap()
+        })
         .sum::<u64>()
         .to_string()
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "32T3K 765
-T55J5 684
-KK677 28
-KTJJT 220
-QQQJA 483";
-
-    const PART_1_EXPECTED: &str = "6440";
-    const PART_2_EXPECTED: &str = "5905";
-
-    #[test]
-    fn part_1_toy_input() {
-        let result = process_part1(INPUT);
-        assert_eq!(result, PART_1_EXPECTED);
-    }
-
-    #[test]
-    fn part_2_toy_input() {
-        let result = process_part2(INPUT);
-        assert_eq!(result, PART_2_EXPECTED);
-    }
-}
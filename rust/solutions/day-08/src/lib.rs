@@ -1,63 +1,133 @@
 use std::collections::HashMap;
 
+use aoc_macros::{aoc, aoc_parser};
+use common::graph::brent;
 use nom::{
     bytes::complete::{tag, take, take_until},
     character::complete::newline,
+    combinator::verify,
     multi::separated_list1,
-    sequence::{delimited, separated_pair, terminated},
+    sequence::{delimited, pair, separated_pair, terminated},
     IResult,
 };
 
+common::export_plugin!(day = 8);
+
 // (BBB, BBB) -> tuple ("BBB", "BBB")
 pub fn node_destinations(input: &str) -> IResult<&str, (&str, &str)> {
     let (input, destinations) = delimited(
         tag("("),
-        separated_pair(take(3 as usize), tag(", "), take(3 as usize)),
+        separated_pair(take(3_usize), tag(", "), take(3_usize)),
         tag(")"),
     )(input)?;
 
     Ok((input, destinations))
 }
 
+// Node tag and its (left, right) destination tags
+pub type Node<'a> = (&'a str, (&'a str, &'a str));
+
 // AAA = (BBB, BBB) -> ("AAA", ("BBB", "BBB"))
-pub fn node(input: &str) -> IResult<&str, (&str, (&str, &str))> {
-    let (input, node) = separated_pair(take(3 as usize), tag(" = "), node_destinations)(input)?;
+pub fn node(input: &str) -> IResult<&str, Node<'_>> {
+    let (input, node) = separated_pair(take(3_usize), tag(" = "), node_destinations)(input)?;
 
     Ok((input, node))
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+pub fn nodes(input: &str) -> IResult<&str, Vec<Node<'_>>> {
+    let (input, nodes) = separated_list1(newline, node)(input)?;
+
+    Ok((input, nodes))
+}
+
 pub fn direction_instructions(input: &str) -> IResult<&str, &str> {
     let (input, string) = terminated(take_until("\n"), tag("\n\n"))(input)?;
 
     Ok((input, string))
 }
 
+// The L/R instructions, a blank line, then the nodes
+#[aoc_parser(day = 8)]
+pub fn network(input: &str) -> IResult<&str, (&str, Vec<Node<'_>>)> {
+    let directions = verify(direction_instructions, |directions: &str| {
+        !directions.is_empty() && directions.chars().all(|c| c == 'L' || c == 'R')
+    });
+
+    pair(directions, nodes)(input)
+}
+
+#[aoc(day = 8, part = 1)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(directions = tracing::field::Empty, nodes = tracing::field::Empty)
+    )
+)]
 pub fn process_part1(input: &str) -> String {
     let (input, directions) = direction_instructions(input).expect("Well formed input");
-    let (_, nodes) = separated_list1(newline, node)(input).expect("Well formed nodes");
+    let (_, nodes) = nodes(input).expect("Well formed nodes");
 
     let node_map: HashMap<&str, (&str, &str)> = nodes.into_iter().collect();
 
-    let mut current_node = node_map.get("AAA").unwrap();
-    let mut steps = 0 as u64;
+    #[cfg(feature = "tracing")]
+    tracing::Span::current()
+        .record("directions", directions.len())
+        .record("nodes", node_map.len());
+
+    steps_to_end(&node_map, directions, "AAA", |tag| tag == "ZZZ")
+        .expect("ZZZ should be reachable from AAA")
+        .to_string()
+}
 
-    for dir in directions.chars().cycle() {
-        steps += 1;
+// Walking the network is a deterministic state machine over (node, position in
+// the instructions), with end nodes and missing nodes stopping the walk where
+// it is. Brent's cycle finding says where the walk settles, which is an end
+// node if one can be reached, instead of walking `directions.chars().cycle()`
+// forever when none can.
+fn steps_to_end<'a>(
+    node_map: &HashMap<&'a str, (&'a str, &'a str)>,
+    directions: &str,
+    start: &'a str,
+    is_end: impl Fn(&str) -> bool,
+) -> Option<u64> {
+    let directions = directions.as_bytes();
+
+    let next_state = |state: &Option<(&'a str, usize)>| {
+        let (tag, i) = (*state)?;
+        if is_end(tag) {
+            return *state;
+        }
 
-        let next_node_tag = match dir {
-            'L' => current_node.0,
-            'R' => current_node.1,
+        let (left, right) = node_map.get(tag)?;
+        let next_node_tag = match directions[i] {
+            b'L' => *left,
+            b'R' => *right,
             _ => panic!("Direction instruction should be L or R"),
         };
+        Some((next_node_tag, (i + 1) % directions.len()))
+    };
 
-        if next_node_tag == "ZZZ" {
-            break;
+    let start = Some((start, 0));
+    let cycle = brent(start, next_state);
+
+    // Walk to where it settled, which only counts if it stays put on an end
+    let mut state = start;
+    for _ in 0..cycle.start {
+        let next = next_state(&state);
+
+        #[cfg(feature = "tracing")]
+        if let (Some((tag, i)), Some((node, _))) = (state, next) {
+            tracing::trace!(from = tag, dir = %char::from(directions[i]), node, "step");
         }
 
-        current_node = node_map.get(next_node_tag).unwrap();
+        state = next;
     }
 
-    steps.to_string()
+    let (tag, _) = state?;
+    (cycle.length == 1 && is_end(tag)).then_some(cycle.start as u64)
 }
 
 fn gcd(a: u64, b: u64) -> u64 {
@@ -79,76 +149,40 @@ fn lcm(a: u64, b: u64) -> u64 {
 // at which every path has completed and landed on a '__Z' node
 //
 // I didn't work this out by inspection - looked up spoilers on reddit
+#[aoc(day = 8, part = 2)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(directions = tracing::field::Empty, nodes = tracing::field::Empty)
+    )
+)]
 pub fn process_part2(input: &str) -> String {
     let (input, directions) = direction_instructions(input).expect("Well formed input");
-    let (_, nodes) = separated_list1(newline, node)(input).expect("Well formed nodes");
+    let (_, nodes) = nodes(input).expect("Well formed nodes");
 
     let node_map: HashMap<&str, (&str, &str)> = nodes.into_iter().collect();
-    let starting_node_tags = node_map.keys().filter(|tag| tag.ends_with("A"));
-    let mut path_lengths: Vec<u64> = Vec::new();
 
-    for start in starting_node_tags {
-        let mut current_node = node_map.get(start).unwrap();
-        let mut steps = 0 as u64;
+    #[cfg(feature = "tracing")]
+    tracing::Span::current()
+        .record("directions", directions.len())
+        .record("nodes", node_map.len());
 
-        for dir in directions.chars().cycle() {
-            steps += 1;
+    let starting_node_tags = node_map.keys().filter(|tag| tag.ends_with('A'));
+    let mut path_lengths: Vec<u64> = Vec::new();
 
-            let next_node_tag = match dir {
-                'L' => current_node.0,
-                'R' => current_node.1,
-                _ => panic!("Direction instruction should be L or R"),
-            };
+    for start in starting_node_tags {
+        let steps = steps_to_end(&node_map, directions, start, |tag| tag.ends_with('Z'))
+            .expect("A __Z node should be reachable from every __A node");
 
-            if next_node_tag.ends_with("Z") {
-                break;
-            }
-
-            current_node = node_map.get(next_node_tag).unwrap();
-        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(start, steps, "path length");
 
         path_lengths.push(steps);
     }
 
-    let result = path_lengths
-        .into_iter()
-        .reduce(|acc, n| lcm(acc, n))
-        .unwrap();
+    let result = path_lengths.into_iter().reduce(lcm).unwrap();
 
     result.to_string()
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn part_1_toy_input() {
-        const INPUT: &str = "LLR
-
-AAA = (BBB, BBB)
-BBB = (AAA, ZZZ)
-ZZZ = (ZZZ, ZZZ)";
-
-        const PART_1_EXPECTED: &str = "6";
-        let result = process_part1(INPUT);
-        assert_eq!(result, PART_1_EXPECTED);
-    }
-
-    #[test]
-    fn part_2_toy_input() {
-        const INPUT: &str = "LR
-
-11A = (11B, XXX)
-11B = (XXX, 11Z)
-11Z = (11B, XXX)
-22A = (22B, XXX)
-22B = (22C, 22C)
-22C = (22Z, 22Z)
-22Z = (22B, 22B)
-XXX = (XXX, XXX)";
-        const PART_2_EXPECTED: &str = "6";
-        let result = process_part2(INPUT);
-        assert_eq!(result, PART_2_EXPECTED);
-    }
-}
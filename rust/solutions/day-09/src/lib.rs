@@ -1,6 +1,20 @@
+use aoc_macros::{aoc, aoc_parser};
 use itertools::Itertools;
+use nom::{
+    character::complete::{self, line_ending, space1},
+    multi::separated_list1,
+    IResult,
+};
 
-pub fn get_next_value(input: &Vec<Vec<i64>>) -> i64 {
+common::export_plugin!(day = 9);
+
+// 0 3 6 9 12 15
+#[aoc_parser(day = 9)]
+pub fn histories(input: &str) -> IResult<&str, Vec<Vec<i64>>> {
+    separated_list1(line_ending, separated_list1(space1, complete::i64))(input)
+}
+
+pub fn get_next_value(input: &[Vec<i64>]) -> i64 {
     input.iter().map(|row| *row.last().unwrap()).sum()
 }
 
@@ -20,21 +34,29 @@ pub fn get_history_pyramid(input: Vec<i64>) -> Vec<Vec<i64>> {
         }
     }
 
-    return differences;
+    #[cfg(feature = "tracing")]
+    tracing::trace!(depth = differences.len(), "history pyramid");
+
+    differences
 }
 
+#[aoc(day = 9, part = 1)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(histories = input.lines().count()))
+)]
 pub fn process_part1(input: &str) -> String {
-    let histories = input.lines().map(|line| {
-        line.split_ascii_whitespace()
-            .map(|num| num.parse::<i64>().unwrap())
-            .collect_vec()
-    });
+    let (_, histories) = histories(input).expect("Well formed histories");
 
-    let pyramids = histories.map(|h| get_history_pyramid(h)).collect_vec();
-    pyramids.iter().map(get_next_value).sum::<i64>().to_string()
+    let pyramids = histories.into_iter().map(get_history_pyramid).collect_vec();
+    pyramids
+        .iter()
+        .map(|p| get_next_value(p))
+        .sum::<i64>()
+        .to_string()
 }
 
-pub fn get_prev_value(input: &Vec<Vec<i64>>) -> i64 {
+pub fn get_prev_value(input: &[Vec<i64>]) -> i64 {
     input
         .iter()
         .map(|row| *row.first().unwrap())
@@ -43,37 +65,18 @@ pub fn get_prev_value(input: &Vec<Vec<i64>>) -> i64 {
         .unwrap()
 }
 
+#[aoc(day = 9, part = 2)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(histories = input.lines().count()))
+)]
 pub fn process_part2(input: &str) -> String {
-    let histories = input.lines().map(|line| {
-        line.split_ascii_whitespace()
-            .map(|num| num.parse::<i64>().unwrap())
-            .collect_vec()
-    });
-
-    let pyramids = histories.map(|h| get_history_pyramid(h)).collect_vec();
-    pyramids.iter().map(get_prev_value).sum::<i64>().to_string()
-}
+    let (_, histories) = histories(input).expect("Well formed histories");
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "0 3 6 9 12 15
-1 3 6 10 15 21
-10 13 16 21 30 45";
-
-    const PART_1_EXPECTED: &str = "114";
-    const PART_2_EXPECTED: &str = "2";
-
-    #[test]
-    fn part_1_toy_input() {
-        let result = process_part1(INPUT);
-        assert_eq!(result, PART_1_EXPECTED);
-    }
-
-    #[test]
-    fn part_2_toy_input() {
-        let result = process_part2(INPUT);
-        assert_eq!(result, PART_2_EXPECTED);
-    }
+    let pyramids = histories.into_iter().map(get_history_pyramid).collect_vec();
+    pyramids
+        .iter()
+        .map(|p| get_prev_value(p))
+        .sum::<i64>()
+        .to_string()
 }
@@ -1,15 +1,32 @@
 use std::collections::{HashMap, HashSet};
 
+use aoc_macros::{aoc, aoc_parser};
+use common::{budget, graph::bfs};
 use itertools::Itertools;
+use nom::{
+    bytes::complete::take_while1, character::complete::line_ending, combinator::verify,
+    multi::separated_list1, IResult,
+};
 
-#[derive(Debug)]
+common::export_plugin!(day = 10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     Up,
     Down,
     Left,
     Right,
-    None,
-    Start,
+}
+
+impl Direction {
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
 }
 
 pub trait Directional {
@@ -43,62 +60,159 @@ impl Directional for (usize, usize) {
     }
 }
 
-pub fn path_length(
-    position: (usize, usize),
-    map: &HashMap<(usize, usize), char>,
-    direction: Direction,
-    mut tiles: HashSet<(usize, usize)>,
-) -> Option<HashSet<(usize, usize)>> {
-    if matches!(direction, Direction::Start) {
-        return Some(tiles);
-    } else if matches!(direction, Direction::None) {
-        return None;
-    }
-
-    let next_position = match &direction {
+pub fn step(position: (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+    match direction {
         Direction::Up => position.up(),
         Direction::Down => position.down(),
         Direction::Left => position.left(),
         Direction::Right => position.right(),
-        _ => None,
-    };
+    }
+}
+
+// The directions a tile's pipe leads in. We don't know which pipe is under
+// the S, so it could lead anywhere
+pub fn pipe_directions(tile: char) -> &'static [Direction] {
+    match tile {
+        '|' => &[Direction::Up, Direction::Down],
+        '-' => &[Direction::Left, Direction::Right],
+        'L' => &[Direction::Up, Direction::Right],
+        'J' => &[Direction::Up, Direction::Left],
+        '7' => &[Direction::Down, Direction::Left],
+        'F' => &[Direction::Down, Direction::Right],
+        'S' => &[
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ],
+        _ => &[],
+    }
+}
+
+// Rows of the same width, of pipes and ground, with exactly one start
+#[aoc_parser(day = 10)]
+pub fn sketch(input: &str) -> IResult<&str, Vec<&str>> {
+    let row = take_while1(|c: char| c == '.' || !pipe_directions(c).is_empty());
+
+    verify(separated_list1(line_ending, row), |rows: &[&str]| {
+        let starts: usize = rows.iter().map(|row| row.matches('S').count()).sum();
+        starts == 1 && rows.iter().all(|row| row.len() == rows[0].len())
+    })(input)
+}
+
+// Neighbouring tiles joined to this one by a pipe that runs both ways. The S
+// joins only the two that lead round the loop and back to it, not a dead end
+// that happens to point at it
+pub fn connected_tiles(
+    position: (usize, usize),
+    map: &HashMap<(usize, usize), char>,
+) -> Vec<(usize, usize)> {
+    let joined = joined_tiles(position, map);
+    if map.get(&position) != Some(&'S') {
+        return joined;
+    }
+
+    joined
+        .into_iter()
+        .filter(|&next| leads_back(position, next, map))
+        .collect()
+}
+
+// Follows the pipe from `start` through `next` until it comes back to `start`
+// or ends. Every tile but the S has two ends, so there's only one way on.
+fn leads_back(
+    start: (usize, usize),
+    next: (usize, usize),
+    map: &HashMap<(usize, usize), char>,
+) -> bool {
+    let (mut previous, mut position) = (start, next);
+    loop {
+        budget::tick();
+        let Some(next) = joined_tiles(position, map)
+            .into_iter()
+            .find(|&tile| tile != previous)
+        else {
+            return false;
+        };
+        if next == start {
+            return true;
+        }
+        (previous, position) = (position, next);
+    }
+}
+
+// Neighbouring tiles joined to this one, with the S leading anywhere
+fn joined_tiles(
+    position: (usize, usize),
+    map: &HashMap<(usize, usize), char>,
+) -> Vec<(usize, usize)> {
+    let tile = map.get(&position).unwrap_or(&'.');
+
+    pipe_directions(*tile)
+        .iter()
+        .filter_map(|direction| {
+            let next_position = step(position, *direction)?;
+            let next_tile = map.get(&next_position).unwrap_or(&'.');
+
+            pipe_directions(*next_tile)
+                .contains(&direction.opposite())
+                .then_some(next_position)
+        })
+        .collect()
+}
+
+// Distance of every tile in the loop from the start, searching outwards along
+// the pipes in both directions at once
+pub fn loop_distances(
+    start: (usize, usize),
+    map: &HashMap<(usize, usize), char>,
+) -> HashMap<(usize, usize), usize> {
+    bfs(start, |&position| {
+        let next_positions = connected_tiles(position, map);
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(?position, ?next_positions, "followed pipe");
+
+        next_positions
+    })
+}
 
-    if next_position.is_none() {
-        return None;
+// Tiles inside the loop, row by row. Scanning along each row, count how many
+// of the loop's pipes leading up (|, L, J) have been crossed to get to a tile.
+// If it's odd, the tile is inside.
+pub fn enclosed_tiles(
+    path_positions: &HashMap<(usize, usize), usize>,
+    map: &HashMap<(usize, usize), char>,
+) -> Vec<(usize, usize)> {
+    let rows = map.keys().map(|&(i, _)| i + 1).max().unwrap_or(0);
+    let cols = map.keys().map(|&(_, j)| j + 1).max().unwrap_or(0);
+
+    let mut enclosed = Vec::new();
+
+    for i in 0..rows {
+        let mut pipes_crossed = 0;
+        for j in 0..cols {
+            budget::tick();
+            if path_positions.contains_key(&(i, j)) {
+                // Pipes leading up (|, L, J, or an S standing in for one) cross the row
+                let up = (i, j).up();
+                if up.is_some_and(|up| connected_tiles((i, j), map).contains(&up)) {
+                    pipes_crossed += 1;
+                }
+            } else if pipes_crossed % 2 > 0 {
+                enclosed.push((i, j));
+            }
+        }
     }
 
-    let next_char = map.get(&next_position.unwrap()).unwrap_or(&'.');
-    let next_direction = match next_char {
-        'J' => match &direction {
-            Direction::Right => Direction::Up,
-            Direction::Down => Direction::Left,
-            _ => Direction::None,
-        },
-        'L' => match &direction {
-            Direction::Left => Direction::Up,
-            Direction::Down => Direction::Right,
-            _ => Direction::None,
-        },
-        'F' => match &direction {
-            Direction::Left => Direction::Down,
-            Direction::Up => Direction::Right,
-            _ => Direction::None,
-        },
-        '7' => match &direction {
-            Direction::Right => Direction::Down,
-            Direction::Up => Direction::Left,
-            _ => Direction::None,
-        },
-        '-' => direction,
-        '|' => direction,
-        'S' => Direction::Start,
-        _ => Direction::None,
-    };
-
-    tiles.insert(position);
-    path_length(next_position.unwrap(), map, next_direction, tiles)
+    enclosed
 }
 
+#[aoc(day = 10, part = 1)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(loop_length = tracing::field::Empty))
+)]
 pub fn process_part1(input: &str) -> String {
     let grid = input.lines().enumerate().flat_map(|(i, line)| {
         line.chars()
@@ -110,27 +224,22 @@ pub fn process_part1(input: &str) -> String {
 
     let map: HashMap<(usize, usize), char> = HashMap::from_iter(grid);
 
-    let mut steps: usize = 0;
-
-    for direction in [
-        Direction::Up,
-        Direction::Down,
-        Direction::Left,
-        Direction::Right,
-    ] {
-        if let Some(tiles) = path_length(position, &map, direction, HashSet::new()) {
-            steps = tiles.len() / 2;
-            break;
-        }
-    }
+    let distances = loop_distances(position, &map);
+
+    #[cfg(feature = "tracing")]
+    tracing::Span::current().record("loop_length", distances.len());
+
+    let steps = distances.into_values().max().unwrap();
 
     steps.to_string()
 }
 
+#[aoc(day = 10, part = 2)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(loop_length = tracing::field::Empty))
+)]
 pub fn process_part2(input: &str) -> String {
-    // Collect travelled tiles in hashset
-    // Then for each line in grid, check how many vertical downward (|, L, J) tiles are crossed
-    // to get to it. If Odd number > 0, it's inside the path
     let grid = input.lines().enumerate().flat_map(|(i, line)| {
         line.chars()
             .enumerate()
@@ -141,74 +250,69 @@ pub fn process_part2(input: &str) -> String {
 
     let map: HashMap<(usize, usize), char> = HashMap::from_iter(grid);
 
-    let path_positions = [
-        Direction::Up,
-        Direction::Down,
-        Direction::Left,
-        Direction::Right,
-    ]
-    .into_iter()
-    .filter_map(|direction| path_length(position, &map, direction, HashSet::new()))
-    .next()
-    .unwrap();
-
-    let rows = input.lines().collect_vec().len();
-    let cols = map.len() / rows;
+    let path_positions = loop_distances(position, &map);
 
-    let mut enclosed: usize = 0;
+    #[cfg(feature = "tracing")]
+    tracing::Span::current().record("loop_length", path_positions.len());
 
-    for i in 0..rows {
-        let mut pipes_crossed = 0;
-        for j in 0..cols {
-            if path_positions.contains(&(i, j)) {
-                match map.get(&(i, j)).unwrap() {
-                    // Technically I should handle whether S is a vertical pipe or not!
-                    '|' | 'L' | 'J' | 'S' => pipes_crossed += 1,
-                    _ => (),
-                };
-            } else if pipes_crossed % 2 > 0 {
-                enclosed += 1;
-            }
-        }
-    }
-
-    enclosed.to_string()
+    enclosed_tiles(&path_positions, &map).len().to_string()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+// Flood fill alternative to the parity scan. Each tile becomes a 3x3 block with
+// the loop's pipes drawn through the middle, so the outside can squeeze between
+// pipes that touch without joining. Whatever the flood from the corner doesn't
+// reach, and isn't loop, is enclosed.
+#[aoc(day = 10, part = 2, name = "flood-fill")]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(loop_length = tracing::field::Empty))
+)]
+pub fn process_part2_flood_fill(input: &str) -> String {
+    let grid = input.lines().enumerate().flat_map(|(i, line)| {
+        line.chars()
+            .enumerate()
+            .map(move |(j, char)| ((i, j), char))
+    });
+
+    let position = grid.clone().find(|entry| entry.1 == 'S').unwrap().0;
 
-    #[test]
-    fn part_1_toy_input() {
-        const INPUT: &str = "7-F7-
-.FJ|7
-SJLL7
-|F--J
-LJ.LJ";
+    let map: HashMap<(usize, usize), char> = HashMap::from_iter(grid);
 
-        const PART_1_EXPECTED: &str = "8";
+    let path_positions = loop_distances(position, &map);
 
-        let result = process_part1(INPUT);
-        assert_eq!(result, PART_1_EXPECTED);
-    }
+    #[cfg(feature = "tracing")]
+    tracing::Span::current().record("loop_length", path_positions.len());
+
+    let rows = input.lines().count();
+    let cols = map.len() / rows;
 
-    #[test]
-    fn part_2_toy_input() {
-        const INPUT: &str = "FF7FSF7F7F7F7F7F---7
-L|LJ||||||||||||F--J
-FL-7LJLJ||||||LJL-77
-F--JF--7||LJLJ7F7FJ-
-L---JF-JLJ.||-FJLJJ7
-|F|F-JF---7F7-L7L|7|
-|FFJF7L7F-JF7|JL---7
-7-L-JL7||F7|L7F-7F7|
-L.L7LFJ|||||FJL7||LJ
-L7JLJL-JLJLJL--JLJ.L";
-
-        const PART_2_EXPECTED: &str = "10";
-
-        let result = process_part2(INPUT);
-        assert_eq!(result, PART_2_EXPECTED);
+    let mut pipes = HashSet::new();
+    for &(i, j) in path_positions.keys() {
+        let centre = (3 * i + 1, 3 * j + 1);
+        pipes.insert(centre);
+        for (ni, nj) in connected_tiles((i, j), &map) {
+            // Halfway from this tile's centre towards the next one's
+            pipes.insert((centre.0 + ni - i, centre.1 + nj - j));
+        }
     }
+
+    let outside = bfs((0, 0), |&(i, j): &(usize, usize)| {
+        [
+            (i.wrapping_sub(1), j),
+            (i + 1, j),
+            (i, j.wrapping_sub(1)),
+            (i, j + 1),
+        ]
+        .into_iter()
+        .filter(|&(i, j)| i < 3 * rows && j < 3 * cols && !pipes.contains(&(i, j)))
+        .collect_vec()
+    });
+
+    (0..rows)
+        .cartesian_product(0..cols)
+        .filter(|&(i, j)| {
+            !path_positions.contains_key(&(i, j)) && !outside.contains_key(&(3 * i + 1, 3 * j + 1))
+        })
+        .count()
+        .to_string()
 }
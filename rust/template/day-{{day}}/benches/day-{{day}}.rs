@@ -0,0 +1,18 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_{{day}}::{process_part1, process_part2};
+use std::hint::black_box;
+
+// Swap in the real input.txt once it's downloaded for meaningful numbers
+const INPUT: &str = include_str!("../../tests/fixtures/day-{{day}}/example.txt");
+
+fn parts(c: &mut Criterion) {
+    c.bench_function("day {{day}} part 1", |b| {
+        b.iter(|| process_part1(black_box(INPUT)))
+    });
+    c.bench_function("day {{day}} part 2", |b| {
+        b.iter(|| process_part2(black_box(INPUT)))
+    });
+}
+
+criterion_group!(benches, parts);
+criterion_main!(benches);
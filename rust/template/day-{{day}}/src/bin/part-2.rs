@@ -0,0 +1,8 @@
+use day_{{day}}::process_part2;
+use std::{error::Error, fs};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let file = fs::read_to_string("./input.txt")?;
+    println!("{}", process_part2(&file)?);
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use aoc_macros::{aoc, aoc_parser};
+use common::parse::finish;
+use nom::{
+    character::complete::{line_ending, not_line_ending},
+    multi::separated_list1,
+    IResult,
+};
+
+common::export_plugin!(day = {{day}});
+
+// Replace with a model of the day's input
+#[derive(Debug, PartialEq)]
+pub struct Puzzle<'a> {
+    pub lines: Vec<&'a str>,
+}
+
+#[aoc_parser(day = {{day}})]
+fn puzzle(input: &str) -> IResult<&str, Puzzle<'_>> {
+    let (input, lines) = separated_list1(line_ending, not_line_ending)(input)?;
+
+    Ok((input, Puzzle { lines }))
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+pub fn parse(input: &str) -> common::Result<Puzzle<'_>> {
+    finish(puzzle, input)
+}
+
+#[aoc(day = {{day}}, part = 1)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(lines = input.lines().count()))
+)]
+pub fn process_part1(input: &str) -> common::Result<String> {
+    let _puzzle = parse(input)?;
+
+    Ok("placeholder".to_string())
+}
+
+#[aoc(day = {{day}}, part = 2)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(lines = input.lines().count()))
+)]
+pub fn process_part2(input: &str) -> common::Result<String> {
+    let _puzzle = parse(input)?;
+
+    Ok("placeholder".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Example answers are checked by the runner's fixture harness, these are for the parser
+    const EXAMPLE: &str = include_str!("../../tests/fixtures/day-{{day}}/example.txt");
+
+    #[test]
+    fn day_{{day}}_parses_example() {
+        if let Err(err) = parse(EXAMPLE) {
+            panic!("day {{day}} example should parse: {err}");
+        }
+    }
+}
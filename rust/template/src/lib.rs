@@ -1,33 +0,0 @@
-use itertools::Itertools;
-
-pub fn process_part1(input: &str) -> String {
-    "placeholder".to_string()
-}
-
-pub fn process_part2(_input: &str) -> String {
-    "placeholder".to_string()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "
-";
-
-    const PART_1_EXPECTED: &str = "";
-    const PART_2_EXPECTED: &str = "";
-
-    #[test]
-    fn part_1_toy_input() {
-        let result = process_part1(INPUT);
-        assert_eq!(result, PART_1_EXPECTED);
-    }
-
-    #[ignore] // Remove when doing part 2
-    #[test]
-    fn part_2_toy_input() {
-        let result = process_part2(INPUT);
-        assert_eq!(result, PART_2_EXPECTED);
-    }
-}